@@ -0,0 +1,18 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use into_the_magical_forest::maze_gen::Maze;
+
+// Wilson's algorithm from a fixed seed, up to the 2000 by 2000 mazes it has to manage in well
+// under a second. That one took about 0.45 s when the walk was last reworked
+fn generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wilsons");
+    group.sample_size(10);
+    for size in [100, 500, 2000] {
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter(|| Maze::from_seed(size, size, 1))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, generation);
+criterion_main!(benches);
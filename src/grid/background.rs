@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use bevy::{
+    asset::AssetIoError,
+    prelude::*,
+    window::{PrimaryWindow, WindowResized},
+};
+
+use super::layout::NavigationSettings;
+use super::player::{PlayerLocation, PlayerMoved};
+use super::transition::Transition;
+use crate::scene::{Scene, SceneLookup};
+use crate::MazeComponent;
+use into_the_magical_forest::maze_gen::CellPos;
+
+// where the backgrounds are looked for, relative to the assets folder. They're named after the
+// square they belong to as x-y.png, counted from 1-1 in the bottom left corner the same way the
+// page titles are, and default.png is used for every square that doesn't have its own
+const BACKGROUND_FOLDER: &str = "sprites/backgrounds/grid";
+// the backgrounds are drawn for this size and scaled to fit the window
+const BACKGROUND_SIZE: (f32, f32) = (1024., 800.);
+
+// every background image there is, found by discover_backgrounds when the game starts
+#[derive(Resource, Default, PartialEq)]
+pub struct Backgrounds {
+    // by location, see MazeComponent::location
+    images: HashMap<(usize, usize), String>,
+    default: Option<String>,
+    // files in the folder that aren't named after a square
+    ignored: Vec<String>,
+}
+impl Backgrounds {
+    // the background image for a square, None if neither it nor a default one exists
+    pub fn image_path(&self, location: (usize, usize)) -> Option<&str> {
+        self.images
+            .get(&location)
+            .or(self.default.as_ref())
+            .map(String::as_str)
+    }
+}
+
+// there should only ever be one ActiveBackground instance, it changes as the player moves. While
+// a transition runs the old one stays underneath the new one until it's faded in
+#[derive(Component)]
+pub struct ActiveBackground {
+    pub location: CellPos,
+}
+
+// the square a background is for from its file name, x-y counted from 1
+fn parse_location(name: &str) -> Option<(usize, usize)> {
+    let (x, y) = name.split_once('-')?;
+    let x: usize = x.trim().parse().ok()?;
+    let y: usize = y.trim().parse().ok()?;
+    Some((x, y)).filter(|&(x, y)| x > 0 && y > 0)
+}
+
+impl Backgrounds {
+    // everything in BACKGROUND_FOLDER, as it is right now
+    fn read(asset_server: &AssetServer) -> Result<Self, AssetIoError> {
+        let mut backgrounds = Backgrounds::default();
+        for path in asset_server
+            .asset_io()
+            .read_directory(Path::new(BACKGROUND_FOLDER))?
+        {
+            if path.extension().and_then(|e| e.to_str()) != Some("png") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let Some(image_path) = path.to_str().map(|path| path.replace('\\', "/")) else {
+                continue;
+            };
+
+            if name == "default" {
+                backgrounds.default = Some(image_path);
+            } else if let Some(location) = parse_location(name) {
+                backgrounds.images.insert(location, image_path);
+            } else {
+                backgrounds.ignored.push(image_path);
+            }
+        }
+        Ok(backgrounds)
+    }
+
+    fn log(&self) {
+        for image_path in &self.ignored {
+            warn!("ignoring background {image_path}, it should be named x-y.png");
+        }
+        info!(
+            "found {} backgrounds in {BACKGROUND_FOLDER}",
+            self.images.len()
+        );
+    }
+}
+
+pub fn discover_backgrounds(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let backgrounds = Backgrounds::read(&asset_server).unwrap_or_else(|error| {
+        warn!("couldn't read the backgrounds in {BACKGROUND_FOLDER}: {error}");
+        Backgrounds::default()
+    });
+    backgrounds.log();
+    commands.insert_resource(backgrounds);
+}
+
+// picks up backgrounds that were added, renamed or removed while the game is running. Changes to
+// the images themselves are reloaded by the asset server
+pub fn rescan_backgrounds(asset_server: Res<AssetServer>, mut backgrounds: ResMut<Backgrounds>) {
+    let Ok(found) = Backgrounds::read(&asset_server) else {
+        return;
+    };
+    if *backgrounds != found {
+        found.log();
+        *backgrounds = found;
+    }
+}
+
+// the largest size that fits in the window while keeping the aspect ratio of the backgrounds
+fn background_scale(width: f32, height: f32) -> f32 {
+    f32::min(width / BACKGROUND_SIZE.0, height / BACKGROUND_SIZE.1)
+}
+
+// a dark green that's a bit different for every square, for the ones without a background
+fn fallback_tint(cell: CellPos) -> Color {
+    let shade = (cell.row * 7 + cell.col * 13) % 8;
+    Color::hsl(90.0 + shade as f32 * 10.0, 0.3, 0.2)
+}
+
+// the image and how to draw it for the background of a square. A scene's background comes before
+// the ones in the backgrounds folder
+fn background_sprite(
+    asset_server: &AssetServer,
+    backgrounds: &Backgrounds,
+    scenes: &SceneLookup,
+    maze: &MazeComponent,
+    cell: CellPos,
+) -> (Handle<Image>, Sprite) {
+    let scene_background = scenes
+        .get(cell)
+        .and_then(|scene| scene.background.as_deref());
+    match scene_background.or_else(|| backgrounds.image_path(maze.location(cell))) {
+        Some(image_path) => (asset_server.load(image_path), Sprite::default()),
+        // the default texture is plain white, so this draws the tint on its own
+        None => (
+            Handle::default(),
+            Sprite {
+                color: fallback_tint(cell),
+                custom_size: Some(Vec2::new(BACKGROUND_SIZE.0, BACKGROUND_SIZE.1)),
+                ..default()
+            },
+        ),
+    }
+}
+
+pub fn spawn_background(
+    mut commands: Commands,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    asset_server: Res<AssetServer>,
+    backgrounds: Res<Backgrounds>,
+    scenes: SceneLookup,
+    maze_query: Query<&MazeComponent>,
+    player_location: Res<PlayerLocation>,
+) {
+    let window = window_query.get_single().unwrap();
+    let (texture, sprite) = background_sprite(
+        &asset_server,
+        &backgrounds,
+        &scenes,
+        maze_query.get_single().unwrap(),
+        player_location.0,
+    );
+
+    commands.spawn((
+        SpriteBundle {
+            sprite,
+            texture,
+            transform: Transform::from_xyz(window.width() / 2., window.height() / 2., 0.)
+                .with_scale(Vec3::splat(background_scale(
+                    window.width(),
+                    window.height(),
+                ))),
+            ..Default::default()
+        },
+        ActiveBackground {
+            location: player_location.0,
+        },
+    ));
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn change_background(
+    mut commands: Commands,
+    mut moved_reader: EventReader<PlayerMoved>,
+    asset_server: Res<AssetServer>,
+    backgrounds: Res<Backgrounds>,
+    scenes: SceneLookup,
+    maze_query: Query<&MazeComponent>,
+    settings: Res<NavigationSettings>,
+    mut background_query: Query<(
+        Entity,
+        &mut ActiveBackground,
+        &mut Handle<Image>,
+        &mut Sprite,
+        &Transform,
+    )>,
+) {
+    // only the latest move matters if several happened in the same frame
+    let Some(moved) = moved_reader.iter().last() else {
+        return;
+    };
+    let (texture, mut sprite) = background_sprite(
+        &asset_server,
+        &backgrounds,
+        &scenes,
+        maze_query.get_single().unwrap(),
+        moved.to,
+    );
+
+    for (entity, mut active, mut old_texture, mut old_sprite, transform) in
+        background_query.iter_mut()
+    {
+        if !settings.transition.animated() {
+            active.location = moved.to;
+            (*old_texture, *old_sprite) = (texture.clone(), sprite.clone());
+            continue;
+        }
+
+        // crossfaded by a new background on top of this one
+        commands.entity(entity).insert(Transition::new(
+            &settings.transition,
+            moved.direction,
+            true,
+        ));
+        sprite.color.set_a(0.);
+        commands.spawn((
+            SpriteBundle {
+                sprite: sprite.clone(),
+                texture: texture.clone(),
+                transform: transform.with_translation(transform.translation + Vec3::Z),
+                ..Default::default()
+            },
+            ActiveBackground { location: moved.to },
+            Transition::new(&settings.transition, moved.direction, false),
+        ));
+    }
+}
+
+// shows the new background straight away, without a crossfade, when the backgrounds or the scenes
+// change on disk
+#[allow(clippy::type_complexity)]
+pub fn refresh_background(
+    mut scene_events: EventReader<AssetEvent<Scene>>,
+    asset_server: Res<AssetServer>,
+    backgrounds: Res<Backgrounds>,
+    scenes: SceneLookup,
+    maze_query: Query<&MazeComponent>,
+    mut background_query: Query<
+        (&ActiveBackground, &mut Handle<Image>, &mut Sprite),
+        Without<Transition>,
+    >,
+) {
+    let scenes_changed = scene_events.iter().count() > 0;
+    if !scenes_changed && !backgrounds.is_changed() {
+        return;
+    }
+    // the backgrounds can change before there's a maze to show them in
+    let Ok(maze) = maze_query.get_single() else {
+        return;
+    };
+    for (active, mut texture, mut sprite) in background_query.iter_mut() {
+        (*texture, *sprite) =
+            background_sprite(&asset_server, &backgrounds, &scenes, maze, active.location);
+    }
+}
+
+// the size of the background should change as the window is resized to fit the greatest size it
+// can on the screen while still maintaining the original aspect ratio.
+pub fn maintain_background(
+    mut resize_reader: EventReader<WindowResized>,
+    mut background_query: Query<&mut Transform, With<ActiveBackground>>,
+) {
+    for e in resize_reader.iter() {
+        for mut transform in background_query.iter_mut() {
+            transform.scale = Vec3::splat(background_scale(e.width, e.height));
+        }
+    }
+}
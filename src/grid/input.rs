@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use super::player::MoveRequest;
+use into_the_magical_forest::maze_gen::Direction;
+
+// which keys and gamepad buttons move the player. Replace or edit the resource to rebind them
+#[derive(Resource)]
+pub struct InputBindings {
+    pub keys: Vec<(KeyCode, Direction)>,
+    pub gamepad_buttons: Vec<(GamepadButtonType, Direction)>,
+    // how far the left stick has to be pushed before it counts as a direction
+    pub stick_threshold: f32,
+    // holding a direction moves once straight away, again after repeat_delay, and then every
+    // repeat_interval after that
+    pub repeat_delay: Duration,
+    pub repeat_interval: Duration,
+}
+impl Default for InputBindings {
+    fn default() -> Self {
+        InputBindings {
+            keys: vec![
+                (KeyCode::Up, Direction::Up),
+                (KeyCode::Right, Direction::Right),
+                (KeyCode::Down, Direction::Down),
+                (KeyCode::Left, Direction::Left),
+                (KeyCode::W, Direction::Up),
+                (KeyCode::D, Direction::Right),
+                (KeyCode::S, Direction::Down),
+                (KeyCode::A, Direction::Left),
+            ],
+            gamepad_buttons: vec![
+                (GamepadButtonType::DPadUp, Direction::Up),
+                (GamepadButtonType::DPadRight, Direction::Right),
+                (GamepadButtonType::DPadDown, Direction::Down),
+                (GamepadButtonType::DPadLeft, Direction::Left),
+            ],
+            stick_threshold: 0.5,
+            repeat_delay: Duration::from_millis(400),
+            repeat_interval: Duration::from_millis(150),
+        }
+    }
+}
+
+// the direction currently held down on the keyboard or gamepad, if any.
+// The on screen buttons read this to light up along with the keys
+#[derive(Resource, Default)]
+pub struct HeldDirection {
+    pub direction: Option<Direction>,
+    repeat: Timer,
+    // the stick has no just_pressed, so its direction from last frame is kept to find new pushes
+    stick: Option<Direction>,
+}
+
+#[derive(SystemParam)]
+pub struct GamepadInput<'w> {
+    gamepads: Res<'w, Gamepads>,
+    buttons: Res<'w, Input<GamepadButton>>,
+    axes: Res<'w, Axis<GamepadAxis>>,
+}
+
+pub fn read_movement_input(
+    time: Res<Time>,
+    bindings: Res<InputBindings>,
+    keys: Res<Input<KeyCode>>,
+    gamepad_input: GamepadInput,
+    mut held: ResMut<HeldDirection>,
+    mut move_writer: EventWriter<MoveRequest>,
+) {
+    let mut pressed = Vec::new();
+    let mut just_pressed = Vec::new();
+
+    for &(key, direction) in &bindings.keys {
+        if keys.pressed(key) {
+            pressed.push(direction);
+        }
+        if keys.just_pressed(key) {
+            just_pressed.push(direction);
+        }
+    }
+
+    for gamepad in gamepad_input.gamepads.iter() {
+        for &(button_type, direction) in &bindings.gamepad_buttons {
+            let button = GamepadButton::new(gamepad, button_type);
+            if gamepad_input.buttons.pressed(button) {
+                pressed.push(direction);
+            }
+            if gamepad_input.buttons.just_pressed(button) {
+                just_pressed.push(direction);
+            }
+        }
+    }
+
+    let stick = gamepad_input.gamepads.iter().find_map(|gamepad| {
+        stick_direction(&gamepad_input.axes, gamepad, bindings.stick_threshold)
+    });
+    if let Some(direction) = stick {
+        pressed.push(direction);
+        if held.stick != stick {
+            just_pressed.push(direction);
+        }
+    }
+    held.stick = stick;
+
+    // a new press always wins, otherwise keep going in the same direction for as long as it's
+    // held, and fall back to anything else that is still held down
+    let direction = just_pressed
+        .first()
+        .copied()
+        .or_else(|| held.direction.filter(|d| pressed.contains(d)))
+        .or_else(|| pressed.first().copied());
+
+    if let Some(direction) = direction {
+        let new_press = !just_pressed.is_empty() || held.direction != Some(direction);
+        if new_press {
+            move_writer.send(MoveRequest(direction));
+            held.repeat = Timer::new(bindings.repeat_delay, TimerMode::Once);
+        } else if held.repeat.tick(time.delta()).just_finished() {
+            move_writer.send(MoveRequest(direction));
+            held.repeat = Timer::new(bindings.repeat_interval, TimerMode::Once);
+        }
+    }
+    held.direction = direction;
+}
+
+fn stick_direction(
+    axes: &Axis<GamepadAxis>,
+    gamepad: Gamepad,
+    threshold: f32,
+) -> Option<Direction> {
+    let x = axes
+        .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+        .unwrap_or(0.);
+    let y = axes
+        .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))
+        .unwrap_or(0.);
+
+    if x.abs().max(y.abs()) < threshold {
+        None
+    } else if x.abs() > y.abs() {
+        Some(if x > 0. {
+            Direction::Right
+        } else {
+            Direction::Left
+        })
+    } else {
+        Some(if y > 0. {
+            Direction::Up
+        } else {
+            Direction::Down
+        })
+    }
+}
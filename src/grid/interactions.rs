@@ -1,75 +1,133 @@
-use crate::MazeComponent;
+use std::f32::consts::TAU;
 
-use super::layout::{build_screen, Direction::*, DirectionButton, Page, CURRENT_LOCATION};
-use super::styles::{HOVERED_BUTTON_COLOR, NORMAL_BUTTON_COLOR, PRESSED_BUTTON_COLOR};
-use super::SIZE;
+use super::input::HeldDirection;
+use super::layout::{
+    Blocked, DirectionButton, NavigationSettings, Page, SceneObjectButton, SceneText,
+};
+use super::player::{MoveBlocked, MoveRequest};
+use crate::styles::Theme;
 use bevy::prelude::*;
+use into_the_magical_forest::maze_gen::Direction;
 
+const BUMP_DURATION: f32 = 0.25;
+const BUMP_DISTANCE: f32 = 12.0;
+
+#[allow(clippy::type_complexity)]
 pub fn interact_with_screen_button(
-    mut commands: Commands,
+    button_query: Query<(&Interaction, &DirectionButton), (Changed<Interaction>, Without<Blocked>)>,
+    mut move_writer: EventWriter<MoveRequest>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if let Interaction::Clicked = *interaction {
+            move_writer.send(MoveRequest(button.direction));
+        }
+    }
+}
+
+// looking at an object puts its description where the scene's was
+pub fn interact_with_scene_object(
+    button_query: Query<(&Interaction, &SceneObjectButton), Changed<Interaction>>,
+    mut text_query: Query<&mut Text, With<SceneText>>,
+) {
+    for (interaction, object) in button_query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        for mut text in text_query.iter_mut() {
+            text.sections[0].value = object.description.clone();
+        }
+    }
+}
+
+// buttons light up when hovered or clicked, and direction buttons also while the matching key or
+// gamepad direction is held so that both ways of moving look like the same control
+#[allow(clippy::type_complexity)]
+pub fn update_button_colors(
+    held: Res<HeldDirection>,
+    theme: Res<Theme>,
     mut button_query: Query<
-        (&Interaction, &mut BackgroundColor, &DirectionButton),
-        Changed<Interaction>,
+        (
+            &Interaction,
+            &mut BackgroundColor,
+            Option<&DirectionButton>,
+            Option<&Blocked>,
+        ),
+        Or<(With<DirectionButton>, With<SceneObjectButton>)>,
     >,
-    page_entity: Query<Entity, With<Page>>,
-    asset_server: Res<AssetServer>,
-    maze_query: Query<&MazeComponent>,
 ) {
-    let maze = maze_query.get_single().unwrap().maze.clone();
-    if let Ok((interaction, mut background_color, button)) = button_query.get_single_mut() {
-        match *interaction {
-            Interaction::Clicked => {
-                *background_color = PRESSED_BUTTON_COLOR.into();
-                if let Ok(page_entity) = page_entity.get_single() {
-                    commands.entity(page_entity).despawn_recursive();
+    for (interaction, mut background_color, button, blocked) in button_query.iter_mut() {
+        let held_down = button.is_some_and(|button| held.direction == Some(button.direction));
+        let color = if blocked.is_some() {
+            theme.buttons.disabled
+        } else if held_down {
+            theme.buttons.pressed
+        } else {
+            match *interaction {
+                Interaction::Clicked => theme.buttons.pressed,
+                Interaction::Hovered => theme.buttons.hovered,
+                Interaction::None => theme.buttons.normal,
+            }
+        };
 
-                    let direction = &button.direction;
+        // only touch the colour when it changes, so change detection stays meaningful
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+    }
+}
 
-                    unsafe {
-                        match direction {
-                            Right => {
-                                if CURRENT_LOCATION.0 < SIZE.0
-                                    && !maze.walls.0[SIZE.1 - CURRENT_LOCATION.1]
-                                        [CURRENT_LOCATION.0 - 1]
-                                {
-                                    CURRENT_LOCATION.0 += 1;
-                                }
-                            }
-                            Left => {
-                                if CURRENT_LOCATION.0 > 1
-                                    && !maze.walls.0[SIZE.1 - CURRENT_LOCATION.1]
-                                        [CURRENT_LOCATION.0 - 2]
-                                {
-                                    CURRENT_LOCATION.0 -= 1;
-                                }
-                            }
-                            Up => {
-                                if CURRENT_LOCATION.1 < SIZE.1
-                                    && !maze.walls.1[SIZE.1 - CURRENT_LOCATION.1 - 1]
-                                        [CURRENT_LOCATION.0 - 1]
-                                {
-                                    CURRENT_LOCATION.1 += 1;
-                                }
-                            }
-                            Down => {
-                                if CURRENT_LOCATION.1 > 1
-                                    && !maze.walls.1[SIZE.1 - CURRENT_LOCATION.1]
-                                        [CURRENT_LOCATION.0 - 1]
-                                {
-                                    CURRENT_LOCATION.1 -= 1;
-                                }
-                            }
-                        }
-                        build_screen(&mut commands, &asset_server, CURRENT_LOCATION)
-                    }
-                }
-            }
-            Interaction::Hovered => {
-                *background_color = HOVERED_BUTTON_COLOR.into();
-            }
-            Interaction::None => {
-                *background_color = NORMAL_BUTTON_COLOR.into();
-            }
+// a short shake of the page towards a wall the player tried to walk into
+#[derive(Component)]
+pub struct Bump {
+    direction: Direction,
+    timer: Timer,
+}
+
+pub fn start_bump(
+    mut commands: Commands,
+    mut blocked_reader: EventReader<MoveBlocked>,
+    settings: Res<NavigationSettings>,
+    page_query: Query<Entity, With<Page>>,
+) {
+    let Some(&MoveBlocked(direction)) = blocked_reader.iter().last() else {
+        return;
+    };
+    if !settings.bump_feedback {
+        return;
+    }
+
+    for page_entity in page_query.iter() {
+        commands.entity(page_entity).insert(Bump {
+            direction,
+            timer: Timer::from_seconds(BUMP_DURATION, TimerMode::Once),
+        });
+    }
+}
+
+pub fn animate_bump(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut bump_query: Query<(Entity, &mut Style, &mut Bump)>,
+) {
+    for (entity, mut style, mut bump) in bump_query.iter_mut() {
+        bump.timer.tick(time.delta());
+        let progress = bump.timer.percent();
+
+        // a sine wave that dies down over the length of the bump
+        let offset = (progress * TAU).sin() * (1. - progress) * BUMP_DISTANCE;
+        let (left, top) = match bump.direction {
+            Direction::Right => (offset, 0.),
+            Direction::Left => (-offset, 0.),
+            Direction::Up => (0., -offset),
+            Direction::Down => (0., offset),
+        };
+        style.position.left = Val::Px(left);
+        style.position.top = Val::Px(top);
+
+        if bump.timer.finished() {
+            style.position.left = Val::Auto;
+            style.position.top = Val::Auto;
+            commands.entity(entity).remove::<Bump>();
         }
     }
 }
@@ -1,59 +1,274 @@
-use super::styles::{
-    get_button_text_style, get_title_text_style, BUTTON_STYLE, MAIN_MENU_STYLE,
-    NORMAL_BUTTON_COLOR, TITLE_STYLE,
+use super::player::{destination, Objective, PlayerLocation, PlayerMoved};
+use super::transition::{start_page_transition, TransitionSettings};
+use crate::scene::{Scene, SceneLookup};
+use crate::styles::{
+    button_style, description_style, get_button_text_style, get_description_text_style,
+    get_disabled_button_text_style, get_seed_text_style, get_title_text_style, main_menu_style,
+    row_style, title_style, Restyle, Theme,
 };
+use crate::{MazeComponent, MazeSeed};
 use bevy::prelude::*;
+use into_the_magical_forest::maze_gen::{CellPos, Direction};
 
 #[derive(Component)]
-pub struct Page {
-    pub location: (usize, usize),
-}
+pub struct Page;
 
 #[derive(Component)]
 pub struct DirectionButton {
     pub direction: Direction,
 }
 
-pub enum Direction {
-    Right,
-    Left,
-    Up,
-    Down,
+// one of the things in the scene that can be looked at, and what's shown when it is
+#[derive(Component)]
+pub struct SceneObjectButton {
+    pub description: String,
 }
 
-pub static mut CURRENT_LOCATION: (usize, usize) = (1, 1);
+// the text under the title, the scene's description until an object is looked at
+#[derive(Component)]
+pub struct SceneText;
 
-pub fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
-    unsafe {
-        build_screen(&mut commands, &asset_server, CURRENT_LOCATION);
+// marks a direction button that leads into a wall. It can't be clicked and doesn't react to
+// hovering
+#[derive(Component)]
+pub struct Blocked;
+
+// the order the buttons are laid out in
+const BUTTON_ORDER: [Direction; 4] = [
+    Direction::Up,
+    Direction::Right,
+    Direction::Left,
+    Direction::Down,
+];
+
+fn direction_label(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Right => "Right",
+        Direction::Left => "Left",
+        Direction::Up => "Up",
+        Direction::Down => "Down",
     }
 }
 
+// how a square is shown to the player, as "x - y" counted from 1 - 1 in the bottom left corner,
+// see MazeComponent::location
+pub fn cell_label(maze: &MazeComponent, cell: CellPos) -> String {
+    let (x, y) = maze.location(cell);
+    format!("{x} - {y}")
+}
+
+// what to do with the buttons for directions that lead into a wall
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlockedExits {
+    // greyed out, in the same place as usual
+    Disabled,
+    // not shown at all
+    Hidden,
+}
+
+// how the maze is shown while playing
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ViewMode {
+    // one square at a time, with a button for every way out of it
+    Rooms,
+    // the whole maze from above, see the top_down module
+    TopDown,
+}
+
+#[derive(Resource)]
+pub struct NavigationSettings {
+    pub view: ViewMode,
+    pub blocked_exits: BlockedExits,
+    // shake the page when trying to walk into a wall from the keyboard or gamepad
+    pub bump_feedback: bool,
+    // the map of the explored squares in the corner of the rooms view
+    pub minimap: bool,
+    // how the rooms view goes from one page to the next
+    pub transition: TransitionSettings,
+}
+impl NavigationSettings {
+    // MAZE_VIEW=top-down starts in the top down view, BLOCKED_EXITS=hidden hides blocked buttons
+    // instead of greying them out, BUMP_FEEDBACK=off turns off the shake and MINIMAP=off the
+    // minimap. See TransitionSettings for the transitions
+    pub fn from_env() -> Self {
+        let view = match std::env::var("MAZE_VIEW").as_deref() {
+            Ok("top-down") => ViewMode::TopDown,
+            _ => ViewMode::Rooms,
+        };
+        let blocked_exits = match std::env::var("BLOCKED_EXITS").as_deref() {
+            Ok("hidden") => BlockedExits::Hidden,
+            _ => BlockedExits::Disabled,
+        };
+        let bump_feedback = !matches!(std::env::var("BUMP_FEEDBACK").as_deref(), Ok("off"));
+        let minimap = !matches!(std::env::var("MINIMAP").as_deref(), Ok("off"));
+
+        NavigationSettings {
+            view,
+            blocked_exits,
+            bump_feedback,
+            minimap,
+            transition: TransitionSettings::from_env(),
+        }
+    }
+}
+
+// run conditions for the systems that only belong to one of the views
+pub fn rooms_view(settings: Res<NavigationSettings>) -> bool {
+    settings.view == ViewMode::Rooms
+}
+
+pub fn top_down_view(settings: Res<NavigationSettings>) -> bool {
+    settings.view == ViewMode::TopDown
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_page(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    seed: Res<MazeSeed>,
+    player_location: Res<PlayerLocation>,
+    objective: Res<Objective>,
+    maze_query: Query<&MazeComponent>,
+    settings: Res<NavigationSettings>,
+    scenes: SceneLookup,
+) {
+    build_screen(
+        &mut commands,
+        &theme,
+        maze_query.get_single().unwrap(),
+        player_location.0,
+        scenes.get(player_location.0),
+        &objective,
+        *seed,
+        &settings,
+    );
+}
+
+// asks for the page to be built again where the player is, without a transition, e.g. when a
+// scene it's built from changed on disk
+pub struct RefreshPage;
+
+// the page is built from the theme, so a new one builds it again, see styles::restyle_screen
+pub fn refresh_page_on_restyle(
+    mut restyle_reader: EventReader<Restyle>,
+    mut refresh_writer: EventWriter<RefreshPage>,
+) {
+    if restyle_reader.iter().count() > 0 {
+        refresh_writer.send(RefreshPage);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn refresh_page(
+    mut commands: Commands,
+    mut refresh_reader: EventReader<RefreshPage>,
+    page_query: Query<Entity, With<Page>>,
+    theme: Res<Theme>,
+    seed: Res<MazeSeed>,
+    player_location: Res<PlayerLocation>,
+    objective: Res<Objective>,
+    maze_query: Query<&MazeComponent>,
+    settings: Res<NavigationSettings>,
+    scenes: SceneLookup,
+) {
+    if refresh_reader.iter().count() == 0 {
+        return;
+    }
+    // the page that's sliding or fading in goes too, the new one takes its place
+    for page_entity in page_query.iter() {
+        commands.entity(page_entity).despawn_recursive();
+    }
+    build_screen(
+        &mut commands,
+        &theme,
+        maze_query.get_single().unwrap(),
+        player_location.0,
+        scenes.get(player_location.0),
+        &objective,
+        *seed,
+        &settings,
+    );
+}
+
+// the page shows the square the player is on, so it gets rebuilt whenever they move
+#[allow(clippy::too_many_arguments)]
+pub fn rebuild_page_on_move(
+    mut commands: Commands,
+    mut moved_reader: EventReader<PlayerMoved>,
+    page_query: Query<Entity, With<Page>>,
+    theme: Res<Theme>,
+    seed: Res<MazeSeed>,
+    objective: Res<Objective>,
+    maze_query: Query<&MazeComponent>,
+    settings: Res<NavigationSettings>,
+    scenes: SceneLookup,
+) {
+    // only the latest move matters if several happened in the same frame
+    if let Some(moved) = moved_reader.iter().last() {
+        let page = build_screen(
+            &mut commands,
+            &theme,
+            maze_query.get_single().unwrap(),
+            moved.to,
+            scenes.get(moved.to),
+            &objective,
+            *seed,
+            &settings,
+        );
+        start_page_transition(
+            &mut commands,
+            &theme,
+            &settings.transition,
+            page_query.iter(),
+            page,
+            moved.direction,
+        );
+    }
+}
+
+// the page for a square, from its scene if it has one. Without a title the page is named after
+// the square, and with one the square is still shown at the bottom so it can be found again
+#[allow(clippy::too_many_arguments)]
 pub fn build_screen(
     commands: &mut Commands,
-    asset_server: &Res<AssetServer>,
-    location: (usize, usize),
-) {
-    let main_menu_entity = commands
+    theme: &Theme,
+    maze: &MazeComponent,
+    location: CellPos,
+    scene: Option<&Scene>,
+    objective: &Objective,
+    seed: MazeSeed,
+    settings: &NavigationSettings,
+) -> Entity {
+    let title = scene.and_then(|scene| scene.title.clone());
+    let mut info = format!(
+        "find the way to {}\nseed: {}",
+        cell_label(maze, objective.goal),
+        seed.0
+    );
+    if title.is_some() {
+        info = format!("{}\n{info}", cell_label(maze, location));
+    }
+
+    commands
         .spawn((
             NodeBundle {
-                style: MAIN_MENU_STYLE,
+                style: main_menu_style(theme),
                 ..Default::default()
             },
-            Page { location },
+            Page,
         ))
         .with_children(|parent| {
             parent
                 .spawn(NodeBundle {
-                    style: TITLE_STYLE,
+                    style: title_style(theme),
                     ..Default::default()
                 })
                 .with_children(|parent| {
                     parent.spawn(TextBundle {
                         text: Text {
                             sections: vec![TextSection::new(
-                                location.0.to_string() + " - " + location.1.to_string().as_str(),
-                                get_title_text_style(&asset_server),
+                                title.unwrap_or_else(|| cell_label(maze, location)),
+                                get_title_text_style(theme),
                             )],
                             alignment: TextAlignment::Center,
                             ..default()
@@ -62,101 +277,118 @@ pub fn build_screen(
                     });
                 });
 
-            parent
-                .spawn((
-                    ButtonBundle {
-                        style: BUTTON_STYLE,
-                        background_color: NORMAL_BUTTON_COLOR.into(),
-                        ..default()
-                    },
-                    DirectionButton {
-                        direction: Direction::Up,
-                    },
-                ))
-                .with_children(|parent| {
-                    parent.spawn(TextBundle {
-                        text: Text {
-                            sections: vec![TextSection::new(
-                                "Up",
-                                get_button_text_style(&asset_server),
-                            )],
-                            alignment: TextAlignment::Center,
-                            ..default()
-                        },
-                        ..default()
-                    });
-                });
-            parent
-                .spawn((
-                    ButtonBundle {
-                        style: BUTTON_STYLE,
-                        background_color: NORMAL_BUTTON_COLOR.into(),
-                        ..default()
-                    },
-                    DirectionButton {
-                        direction: Direction::Right,
-                    },
-                ))
-                .with_children(|parent| {
-                    parent.spawn(TextBundle {
-                        text: Text {
-                            sections: vec![TextSection::new(
-                                "Right",
-                                get_button_text_style(&asset_server),
-                            )],
-                            alignment: TextAlignment::Center,
-                            ..default()
-                        },
-                        ..default()
-                    });
-                });
-            parent
-                .spawn((
-                    ButtonBundle {
-                        style: BUTTON_STYLE,
-                        background_color: NORMAL_BUTTON_COLOR.into(),
-                        ..default()
-                    },
-                    DirectionButton {
-                        direction: Direction::Left,
-                    },
-                ))
-                .with_children(|parent| {
-                    parent.spawn(TextBundle {
-                        text: Text {
-                            sections: vec![TextSection::new(
-                                "Left",
-                                get_button_text_style(&asset_server),
-                            )],
-                            alignment: TextAlignment::Center,
+            if let Some(scene) = scene {
+                spawn_scene(parent, theme, scene);
+            }
+
+            for direction in BUTTON_ORDER {
+                let blocked = destination(maze, location, direction).is_none();
+                match (blocked, settings.blocked_exits) {
+                    (false, _) => spawn_direction_button(parent, theme, direction, false),
+                    (true, BlockedExits::Disabled) => {
+                        spawn_direction_button(parent, theme, direction, true)
+                    }
+                    (true, BlockedExits::Hidden) => {}
+                }
+            }
+
+            // the seed is shown so that it can be included in bug reports
+            parent.spawn(TextBundle {
+                text: Text {
+                    sections: vec![TextSection::new(info, get_seed_text_style(theme))],
+                    alignment: TextAlignment::Center,
+                    ..default()
+                },
+                ..default()
+            });
+        })
+        .id()
+}
+
+// the description and the objects of a scene, between the title and the direction buttons
+fn spawn_scene(parent: &mut ChildBuilder, theme: &Theme, scene: &Scene) {
+    parent.spawn((
+        TextBundle {
+            text: Text {
+                sections: vec![TextSection::new(
+                    scene.description.clone().unwrap_or_default(),
+                    get_description_text_style(theme),
+                )],
+                alignment: TextAlignment::Center,
+                ..default()
+            },
+            style: description_style(theme),
+            ..default()
+        },
+        SceneText,
+    ));
+
+    if scene.objects.is_empty() {
+        return;
+    }
+    parent
+        .spawn(NodeBundle {
+            style: row_style(theme),
+            ..default()
+        })
+        .with_children(|parent| {
+            for object in &scene.objects {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: button_style(theme),
+                            background_color: theme.buttons.normal.into(),
                             ..default()
                         },
-                        ..default()
-                    });
-                });
-            parent
-                .spawn((
-                    ButtonBundle {
-                        style: BUTTON_STYLE,
-                        background_color: NORMAL_BUTTON_COLOR.into(),
-                        ..default()
-                    },
-                    DirectionButton {
-                        direction: Direction::Down,
-                    },
-                ))
-                .with_children(|parent| {
-                    parent.spawn(TextBundle {
-                        text: Text {
-                            sections: vec![TextSection::new(
-                                "Down",
-                                get_button_text_style(&asset_server),
-                            )],
-                            alignment: TextAlignment::Center,
-                            ..default()
+                        SceneObjectButton {
+                            description: object.description.clone(),
                         },
-                        ..default()
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            object.name.clone(),
+                            get_button_text_style(theme),
+                        ));
                     });
-                });
+            }
+        });
+}
+
+fn spawn_direction_button(
+    parent: &mut ChildBuilder,
+    theme: &Theme,
+    direction: Direction,
+    blocked: bool,
+) {
+    let (background_color, text_style) = if blocked {
+        (
+            theme.buttons.disabled,
+            get_disabled_button_text_style(theme),
+        )
+    } else {
+        (theme.buttons.normal, get_button_text_style(theme))
+    };
+
+    let mut button = parent.spawn((
+        ButtonBundle {
+            style: button_style(theme),
+            background_color: background_color.into(),
+            ..default()
+        },
+        DirectionButton { direction },
+    ));
+    if blocked {
+        button.insert(Blocked);
+    }
+
+    button.with_children(|parent| {
+        parent.spawn(TextBundle {
+            text: Text {
+                sections: vec![TextSection::new(direction_label(direction), text_style)],
+                alignment: TextAlignment::Center,
+                ..default()
+            },
+            ..default()
         });
+    });
 }
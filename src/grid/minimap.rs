@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+
+use super::layout::NavigationSettings;
+use super::player::{Exploration, Explored, Objective, PlayerLocation};
+use crate::styles::Theme;
+use crate::MazeComponent;
+use into_the_magical_forest::maze_gen::{CellPos, Direction};
+
+// the longest side of the minimap, in pixels
+const MINIMAP_SIZE: f32 = 220.0;
+const WALL_THICKNESS: f32 = 2.0;
+
+// the whole maze is laid out hidden when playing starts, and squares and walls are shown as
+// they're explored
+#[derive(Component)]
+pub struct Minimap;
+
+#[derive(Component)]
+pub struct MinimapCell(CellPos);
+
+// a side of a square, which is shown once the square on either side of it has been visited
+#[derive(Component)]
+pub struct MinimapWall {
+    cell: CellPos,
+    direction: Direction,
+}
+
+fn visibility(visible: bool) -> Visibility {
+    if visible {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    }
+}
+
+// a node placed on the minimap by its top left corner, relative to the top left of the maze
+fn minimap_node(left: f32, top: f32, width: f32, height: f32, color: Color) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Px(left),
+                top: Val::Px(top),
+                ..default()
+            },
+            size: Size::new(Val::Px(width), Val::Px(height)),
+            ..default()
+        },
+        background_color: color.into(),
+        visibility: Visibility::Hidden,
+        ..default()
+    }
+}
+
+pub fn spawn_minimap(
+    mut commands: Commands,
+    maze_query: Query<&MazeComponent>,
+    settings: Res<NavigationSettings>,
+    theme: Res<Theme>,
+) {
+    let maze = &maze_query.get_single().unwrap().maze;
+    let (width, height) = (maze.width(), maze.height());
+    let cell = (MINIMAP_SIZE / width.max(height) as f32).floor().max(1.0);
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        right: Val::Px(16.0),
+                        bottom: Val::Px(16.0),
+                        ..default()
+                    },
+                    size: Size::new(Val::Px(width as f32 * cell), Val::Px(height as f32 * cell)),
+                    ..default()
+                },
+                background_color: theme.maze.minimap_background.into(),
+                visibility: visibility(settings.minimap),
+                // above the page, which is spawned again on every move, and the fade between pages
+                z_index: ZIndex::Global(2),
+                ..default()
+            },
+            Minimap,
+        ))
+        .with_children(|parent| {
+            for row in 0..height {
+                for col in 0..width {
+                    let square = CellPos::new(row, col);
+                    parent.spawn((
+                        minimap_node(
+                            col as f32 * cell,
+                            row as f32 * cell,
+                            cell,
+                            cell,
+                            theme.maze.minimap_visited,
+                        ),
+                        MinimapCell(square),
+                    ));
+
+                    // every side once: the right and bottom ones of each square, and the top and
+                    // left ones along the outer edges
+                    for direction in Direction::ALL {
+                        let outer = maze.neighbour(square, direction).is_none();
+                        let owned = matches!(direction, Direction::Right | Direction::Down);
+                        if !(owned || outer) || !maze.has_wall(square, direction) {
+                            continue;
+                        }
+
+                        let (x, y) = (col as f32 * cell, row as f32 * cell);
+                        let half = WALL_THICKNESS / 2.0;
+                        let (left, top, w, h) = match direction {
+                            Direction::Up => (x, y - half, cell, WALL_THICKNESS),
+                            Direction::Right => (x + cell - half, y, WALL_THICKNESS, cell),
+                            Direction::Down => (x, y + cell - half, cell, WALL_THICKNESS),
+                            Direction::Left => (x - half, y, WALL_THICKNESS, cell),
+                        };
+                        parent.spawn((
+                            minimap_node(left, top, w, h, theme.maze.minimap_wall),
+                            MinimapWall {
+                                cell: square,
+                                direction,
+                            },
+                        ));
+                    }
+                }
+            }
+        });
+}
+
+// M shows and hides the minimap, the same as the setting for it
+pub fn toggle_minimap(keys: Res<Input<KeyCode>>, mut settings: ResMut<NavigationSettings>) {
+    if keys.just_pressed(KeyCode::M) {
+        settings.minimap = !settings.minimap;
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn update_minimap(
+    explored: Res<Explored>,
+    player_location: Res<PlayerLocation>,
+    objective: Res<Objective>,
+    settings: Res<NavigationSettings>,
+    theme: Res<Theme>,
+    maze_query: Query<&MazeComponent>,
+    mut minimap_query: Query<&mut Visibility, With<Minimap>>,
+    spawned_query: Query<(), Added<Minimap>>,
+    mut cell_query: Query<(&MinimapCell, &mut Visibility, &mut BackgroundColor), Without<Minimap>>,
+    mut wall_query: Query<
+        (&MinimapWall, &mut Visibility),
+        (Without<Minimap>, Without<MinimapCell>),
+    >,
+) {
+    if settings.is_changed() {
+        for mut minimap in minimap_query.iter_mut() {
+            *minimap = visibility(settings.minimap);
+        }
+    }
+    // nothing else changes between moves, except for a minimap that was just spawned
+    if !explored.is_changed() && !player_location.is_changed() && spawned_query.is_empty() {
+        return;
+    }
+    let maze = &maze_query.get_single().unwrap().maze;
+
+    for (MinimapCell(cell), mut visible, mut color) in cell_query.iter_mut() {
+        let goal = *cell == objective.goal;
+        *visible = visibility(match explored.get(*cell) {
+            Exploration::Visited => true,
+            // the goal can be spotted from next door
+            Exploration::Seen => goal,
+            Exploration::Unknown => false,
+        });
+        color.0 = if *cell == player_location.0 {
+            theme.maze.minimap_current
+        } else if goal {
+            theme.maze.goal
+        } else {
+            theme.maze.minimap_visited
+        };
+    }
+
+    for (wall, mut visible) in wall_query.iter_mut() {
+        let seen = explored.visited(wall.cell)
+            || maze
+                .neighbour(wall.cell, wall.direction)
+                .is_some_and(|next| explored.visited(next));
+        *visible = visibility(seen);
+    }
+}
@@ -1,17 +1,154 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, time::common_conditions::on_timer};
 
+mod background;
+use background::{
+    change_background, discover_backgrounds, maintain_background, refresh_background,
+    rescan_backgrounds, spawn_background, ActiveBackground,
+};
+mod input;
+use input::{read_movement_input, HeldDirection, InputBindings};
 mod interactions;
-use interactions::interact_with_screen_button;
+use interactions::{
+    animate_bump, interact_with_scene_object, interact_with_screen_button, start_bump,
+    update_button_colors,
+};
 pub mod layout;
-use layout::spawn_main_menu;
-mod styles;
+use layout::{
+    rebuild_page_on_move, refresh_page, refresh_page_on_restyle, rooms_view, spawn_page,
+    top_down_view, NavigationSettings, Page, RefreshPage,
+};
+mod minimap;
+use minimap::{spawn_minimap, toggle_minimap, update_minimap, Minimap};
+pub mod player;
+use player::{
+    explore, log_player_moves, move_player, Explored, MoveBlocked, MoveRequest, Objective,
+    ObjectiveSettings, PlayerLocation, PlayerMoved, RunStats,
+};
+pub mod run;
+use run::{reach_goal, start_run, tick_run_time, StartRun};
+mod top_down;
+use top_down::{
+    animate_glide, follow_player, reset_camera, spawn_top_down_view, start_glide,
+    update_top_down_label, TopDownView,
+};
+mod transition;
+use transition::{
+    animate_background_transitions, animate_fade_cover, animate_page_transitions, no_transition,
+    FadeCover,
+};
 
-pub const SIZE: (usize, usize) = (9, 9);
+use crate::menus::{despawn_screen, rebuild_on_restyle};
+use crate::{hot_reload, AppState, ASSET_RESCAN_INTERVAL};
 
-pub struct MainMenuPlugin;
-impl Plugin for MainMenuPlugin {
+// moving around the maze while playing
+pub struct NavigationPlugin;
+impl Plugin for NavigationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_startup_system(spawn_main_menu)
-            .add_system(interact_with_screen_button);
+        app.init_resource::<PlayerLocation>()
+            .init_resource::<Objective>()
+            .init_resource::<RunStats>()
+            .init_resource::<Explored>()
+            .insert_resource(ObjectiveSettings::from_env())
+            .init_resource::<InputBindings>()
+            .init_resource::<HeldDirection>()
+            .insert_resource(NavigationSettings::from_env())
+            .add_event::<MoveRequest>()
+            .add_event::<MoveBlocked>()
+            .add_event::<PlayerMoved>()
+            .add_event::<StartRun>()
+            .add_event::<RefreshPage>()
+            .add_system(start_run)
+            .add_system(
+                spawn_page
+                    .run_if(rooms_view)
+                    .in_schedule(OnEnter(AppState::Playing)),
+            )
+            .add_system(despawn_screen::<Page>.in_schedule(OnExit(AppState::Playing)))
+            .add_system(despawn_screen::<FadeCover>.in_schedule(OnExit(AppState::Playing)))
+            .add_startup_system(discover_backgrounds)
+            .add_system(
+                rescan_backgrounds
+                    .run_if(hot_reload)
+                    .run_if(on_timer(ASSET_RESCAN_INTERVAL)),
+            )
+            .add_system(
+                spawn_background
+                    .run_if(rooms_view)
+                    .in_schedule(OnEnter(AppState::Playing)),
+            )
+            .add_system(despawn_screen::<ActiveBackground>.in_schedule(OnExit(AppState::Playing)))
+            .add_system(
+                spawn_minimap
+                    .run_if(rooms_view)
+                    .in_schedule(OnEnter(AppState::Playing)),
+            )
+            .add_system(despawn_screen::<Minimap>.in_schedule(OnExit(AppState::Playing)))
+            .add_system(
+                spawn_top_down_view
+                    .run_if(top_down_view)
+                    .in_schedule(OnEnter(AppState::Playing)),
+            )
+            .add_systems(
+                (despawn_screen::<TopDownView>, reset_camera)
+                    .in_schedule(OnExit(AppState::Playing)),
+            )
+            .add_systems(
+                (
+                    interact_with_screen_button
+                        .before(move_player)
+                        .run_if(no_transition),
+                    read_movement_input
+                        .before(move_player)
+                        .run_if(no_transition),
+                    move_player,
+                    update_button_colors.after(read_movement_input),
+                    interact_with_scene_object,
+                    reach_goal.after(move_player),
+                    rebuild_page_on_move.after(move_player).run_if(rooms_view),
+                    change_background.after(move_player),
+                    log_player_moves.after(move_player),
+                    explore.after(move_player),
+                    start_bump.after(move_player),
+                    animate_bump.after(start_bump),
+                    tick_run_time,
+                    maintain_background,
+                )
+                    .in_set(OnUpdate(AppState::Playing)),
+            )
+            .add_systems(
+                (
+                    start_glide.after(move_player),
+                    animate_glide.after(start_glide),
+                    follow_player.after(animate_glide),
+                    update_top_down_label.after(move_player),
+                )
+                    .distributive_run_if(top_down_view)
+                    .in_set(OnUpdate(AppState::Playing)),
+            )
+            .add_systems(
+                (
+                    toggle_minimap,
+                    update_minimap.after(toggle_minimap).after(explore),
+                    animate_page_transitions.after(animate_bump),
+                    refresh_page_on_restyle,
+                    refresh_page
+                        .after(rebuild_page_on_move)
+                        .after(refresh_page_on_restyle),
+                    refresh_background.after(change_background),
+                    animate_fade_cover,
+                    animate_background_transitions,
+                )
+                    .distributive_run_if(rooms_view)
+                    .in_set(OnUpdate(AppState::Playing)),
+            )
+            // the page is built again in place, the other views from scratch
+            .add_systems(
+                rebuild_on_restyle::<Minimap, _>(AppState::Playing, spawn_minimap)
+                    .distributive_run_if(rooms_view),
+            )
+            .add_systems(
+                rebuild_on_restyle::<TopDownView, _>(AppState::Playing, spawn_top_down_view)
+                    .distributive_run_if(top_down_view),
+            );
     }
 }
@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::MazeComponent;
+use into_the_magical_forest::maze_gen::{CellPos, Direction, Maze};
+
+// the square the player is on, the same CellPos the maze itself uses
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct PlayerLocation(pub CellPos);
+
+// the squares a run starts and ends on
+#[derive(Resource, Clone, Copy, Default, Debug)]
+pub struct Objective {
+    pub start: CellPos,
+    pub goal: CellPos,
+}
+
+// where runs should start and end, as (x, y) the way the page shows squares. Without a goal, it's
+// put on the square furthest away from the start, so that the way there is as long as the maze
+// allows. Squares that don't fit in the maze are ignored
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct ObjectiveSettings {
+    pub start: (usize, usize),
+    pub goal: Option<(usize, usize)>,
+}
+impl ObjectiveSettings {
+    // MAZE_START and MAZE_GOAL take a square as "x,y", counted from 1,1 in the bottom left corner
+    // the same way the page shows it
+    pub fn from_env() -> Self {
+        ObjectiveSettings {
+            start: location_from_env("MAZE_START").unwrap_or((1, 1)),
+            goal: location_from_env("MAZE_GOAL"),
+        }
+    }
+
+    pub fn objective(&self, maze: &MazeComponent) -> Objective {
+        let start = maze
+            .cell_at(self.start)
+            .unwrap_or_else(|| maze.cell_at((1, 1)).unwrap());
+        let goal = self
+            .goal
+            .and_then(|goal| maze.cell_at(goal))
+            .unwrap_or_else(|| maze.maze.farthest_from(start.into()).0.into());
+        Objective { start, goal }
+    }
+}
+
+fn location_from_env(name: &str) -> Option<(usize, usize)> {
+    let value = std::env::var(name).ok()?;
+    let location = value
+        .split_once(',')
+        .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+        .filter(|&(x, y)| x > 0 && y > 0);
+    if location.is_none() {
+        warn!("{name} should be a square as \"x,y\" starting from 1,1, ignoring {value:?}");
+    }
+    location
+}
+
+// squares are shown to the player, and named in MAZE_START, MAZE_GOAL, background files and
+// scenes, as (x, y) starting from (1, 1) in the bottom left corner, so going up increases y.
+// Everything else uses the maze's own CellPos, counted from the top left
+impl MazeComponent {
+    pub fn location(&self, cell: CellPos) -> (usize, usize) {
+        (cell.col + 1, self.maze.height() - cell.row)
+    }
+
+    // None for locations outside of the maze
+    pub fn cell_at(&self, (x, y): (usize, usize)) -> Option<CellPos> {
+        let cell = CellPos::new(self.maze.height().checked_sub(y)?, x.checked_sub(1)?);
+        self.maze.contains(cell).then_some(cell)
+    }
+}
+
+// moves made and time taken so far in the current run
+#[derive(Resource, Default, Debug)]
+pub struct RunStats {
+    pub moves: usize,
+    // the fewest moves the goal can be reached in
+    pub optimal_moves: usize,
+    pub elapsed: Duration,
+}
+impl RunStats {
+    pub fn new(maze: &MazeComponent, objective: &Objective) -> Self {
+        let optimal_moves = maze
+            .maze
+            .shortest_path(objective.start.into(), objective.goal.into())
+            .map_or(0, |path| path.len() - 1);
+        RunStats {
+            moves: 0,
+            optimal_moves,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+// how much the player knows about a square
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+pub enum Exploration {
+    #[default]
+    Unknown,
+    // can be seen through an open passage from a square the player has been on
+    Seen,
+    Visited,
+}
+
+// what the player has found of the maze so far this run, one entry per square row by row.
+// It only holds plain data so that it can be saved along with the rest of a run
+#[derive(Resource, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct Explored {
+    height: usize,
+    width: usize,
+    cells: Vec<Exploration>,
+}
+impl Explored {
+    pub fn new(height: usize, width: usize) -> Self {
+        Explored {
+            height,
+            width,
+            cells: vec![Exploration::Unknown; height * width],
+        }
+    }
+
+    // Unknown for squares outside the maze
+    pub fn get(&self, cell: CellPos) -> Exploration {
+        if cell.row < self.height && cell.col < self.width {
+            self.cells[cell.row * self.width + cell.col]
+        } else {
+            Exploration::Unknown
+        }
+    }
+
+    pub fn visited(&self, cell: CellPos) -> bool {
+        self.get(cell) == Exploration::Visited
+    }
+
+    // the square and everything that can be seen from it
+    pub fn visit(&mut self, maze: &Maze, cell: CellPos) {
+        self.cells[cell.row * self.width + cell.col] = Exploration::Visited;
+        for direction in maze.exits(cell) {
+            if let Some(next) = maze.neighbour(cell, direction) {
+                let seen = &mut self.cells[next.row * self.width + next.col];
+                if *seen == Exploration::Unknown {
+                    *seen = Exploration::Seen;
+                }
+            }
+        }
+    }
+}
+
+// asks for the player to be moved one square. Every way of moving (buttons, keys, gamepad)
+// goes through this so that they all behave the same
+pub struct MoveRequest(pub Direction);
+
+// sent when a move was asked for but there is a wall in the way
+pub struct MoveBlocked(pub Direction);
+
+// sent every time the player actually moves to a new square. Anything that depends on the
+// player's location should listen for this rather than polling PlayerLocation
+pub struct PlayerMoved {
+    pub from: CellPos,
+    pub to: CellPos,
+    pub direction: Direction,
+}
+
+// the square the player ends up on when trying to move in the given direction,
+// or None if there is a wall in the way
+pub fn destination(maze: &MazeComponent, cell: CellPos, direction: Direction) -> Option<CellPos> {
+    if maze.maze.can_move(cell, direction) {
+        maze.maze.neighbour(cell, direction)
+    } else {
+        None
+    }
+}
+
+pub fn move_player(
+    mut move_reader: EventReader<MoveRequest>,
+    maze_query: Query<&MazeComponent>,
+    mut player_location: ResMut<PlayerLocation>,
+    mut stats: ResMut<RunStats>,
+    mut moved_writer: EventWriter<PlayerMoved>,
+    mut blocked_writer: EventWriter<MoveBlocked>,
+) {
+    let maze = maze_query.get_single().unwrap();
+    for &MoveRequest(direction) in move_reader.iter() {
+        let from = player_location.0;
+        match destination(maze, from, direction) {
+            Some(to) => {
+                player_location.0 = to;
+                stats.moves += 1;
+                moved_writer.send(PlayerMoved {
+                    from,
+                    to,
+                    direction,
+                });
+            }
+            None => blocked_writer.send(MoveBlocked(direction)),
+        }
+    }
+}
+
+pub fn explore(
+    mut moved_reader: EventReader<PlayerMoved>,
+    maze_query: Query<&MazeComponent>,
+    mut explored: ResMut<Explored>,
+) {
+    for moved in moved_reader.iter() {
+        explored.visit(&maze_query.get_single().unwrap().maze, moved.to);
+    }
+}
+
+pub fn log_player_moves(mut moved_reader: EventReader<PlayerMoved>) {
+    for moved in moved_reader.iter() {
+        debug!(
+            "player moved {:?} from {:?} to {:?}",
+            moved.direction, moved.from, moved.to
+        );
+    }
+}
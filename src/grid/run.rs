@@ -0,0 +1,87 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use super::layout::cell_label;
+use super::player::{
+    Explored, Objective, ObjectiveSettings, PlayerLocation, PlayerMoved, RunStats,
+};
+use crate::{AppState, MazeComponent, MazeOptions};
+
+// starts a run from the beginning and switches to playing. With regenerate set, a new maze is
+// made from the current MazeOptions first, otherwise the same maze is played again
+pub struct StartRun {
+    pub regenerate: bool,
+}
+
+// everything that has to be put back when a run starts over
+#[derive(SystemParam)]
+pub struct Run<'w> {
+    settings: Res<'w, ObjectiveSettings>,
+    objective: ResMut<'w, Objective>,
+    player_location: ResMut<'w, PlayerLocation>,
+    stats: ResMut<'w, RunStats>,
+    explored: ResMut<'w, Explored>,
+}
+impl Run<'_> {
+    fn reset(&mut self, maze: &MazeComponent) {
+        *self.objective = self.settings.objective(maze);
+        self.player_location.0 = self.objective.start;
+        *self.stats = RunStats::new(maze, &self.objective);
+        *self.explored = Explored::new(maze.maze.height(), maze.maze.width());
+        self.explored.visit(&maze.maze, self.objective.start);
+        info!(
+            "starting at {}, the goal is at {}, {} moves away",
+            cell_label(maze, self.objective.start),
+            cell_label(maze, self.objective.goal),
+            self.stats.optimal_moves
+        );
+    }
+}
+
+pub fn start_run(
+    mut commands: Commands,
+    mut start_reader: EventReader<StartRun>,
+    mut maze_query: Query<&mut MazeComponent>,
+    options: MazeOptions,
+    mut run: Run,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Some(start) = start_reader.iter().last() else {
+        return;
+    };
+
+    match maze_query.get_single_mut() {
+        Ok(mut maze) => {
+            if start.regenerate {
+                maze.maze = options.generate();
+                maze.maze.display_maze();
+            }
+            run.reset(&maze);
+        }
+        // the very first run, there's no maze to play again yet
+        Err(_) => {
+            let maze = MazeComponent {
+                maze: options.generate(),
+            };
+            maze.maze.display_maze();
+            run.reset(&maze);
+            commands.spawn(maze);
+        }
+    }
+
+    next_state.set(AppState::Playing);
+}
+
+// the clock only runs while playing, so time spent paused doesn't count
+pub fn tick_run_time(time: Res<Time>, mut stats: ResMut<RunStats>) {
+    stats.elapsed += time.delta();
+}
+
+pub fn reach_goal(
+    mut moved_reader: EventReader<PlayerMoved>,
+    objective: Res<Objective>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if moved_reader.iter().any(|moved| moved.to == objective.goal) {
+        next_state.set(AppState::Victory);
+    }
+}
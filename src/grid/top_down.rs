@@ -0,0 +1,256 @@
+use std::f32::consts::PI;
+
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use super::layout::{cell_label, NavigationSettings};
+use super::player::{MoveBlocked, Objective, PlayerLocation, PlayerMoved};
+use crate::styles::{get_seed_text_style, overlay_style, Theme};
+use crate::{MazeComponent, MazeSeed};
+use into_the_magical_forest::maze_gen::{CellPos, Direction};
+
+// in world units, which the camera shows one to one as pixels
+const CELL_SIZE: f32 = 64.0;
+const WALL_THICKNESS: f32 = 8.0;
+const PLAYER_SIZE: f32 = 28.0;
+// how long the player takes to walk from one square to the next, a bit less than the time
+// between repeated moves so that holding a direction walks smoothly
+const GLIDE_DURATION: f32 = 0.12;
+// how quickly the camera catches up with the player, higher is faster
+const CAMERA_FOLLOW: f32 = 8.0;
+
+// everything the top down view spawns, despawned again when it's left
+#[derive(Component)]
+pub struct TopDownView;
+
+#[derive(Component)]
+pub struct TopDownLabel;
+
+// the player walks between the middles of squares. Walking into a wall goes up to it and back
+#[derive(Component)]
+pub struct PlayerSprite {
+    from: Vec2,
+    to: Vec2,
+    timer: Timer,
+    blocked: bool,
+}
+
+// the middle of a square, with the top left one at the origin and rows going down
+fn cell_centre(cell: CellPos) -> Vec2 {
+    Vec2::new(cell.col as f32, -(cell.row as f32)) * CELL_SIZE
+}
+
+fn direction_vector(direction: Direction) -> Vec2 {
+    match direction {
+        Direction::Up => Vec2::Y,
+        Direction::Right => Vec2::X,
+        Direction::Down => Vec2::NEG_Y,
+        Direction::Left => Vec2::NEG_X,
+    }
+}
+
+fn rect(color: Color, centre: Vec2, size: Vec2, z: f32) -> (SpriteBundle, TopDownView) {
+    (
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation(centre.extend(z)),
+            ..default()
+        },
+        TopDownView,
+    )
+}
+
+pub fn spawn_top_down_view(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    maze_query: Query<&MazeComponent>,
+    player_location: Res<PlayerLocation>,
+    objective: Res<Objective>,
+    seed: Res<MazeSeed>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+) {
+    let maze_component = maze_query.get_single().unwrap();
+    let maze = &maze_component.maze;
+    let (width, height) = (maze.width() as f32, maze.height() as f32);
+    // the middle of the whole maze and how big it is
+    let middle = Vec2::new(width - 1.0, -(height - 1.0)) * CELL_SIZE / 2.0;
+    let size = Vec2::new(width, height) * CELL_SIZE;
+
+    commands.spawn(rect(theme.maze.floor, middle, size, 0.0));
+    commands.spawn(rect(
+        theme.maze.goal,
+        cell_centre(objective.goal),
+        Vec2::splat(CELL_SIZE - 2.0 * WALL_THICKNESS),
+        0.5,
+    ));
+
+    for (cell, direction) in maze.inner_walls() {
+        // the wall sits on the edge between this square and the next one
+        let centre = cell_centre(cell) + direction_vector(direction) * CELL_SIZE / 2.0;
+        let size = match direction {
+            Direction::Right => Vec2::new(WALL_THICKNESS, CELL_SIZE + WALL_THICKNESS),
+            _ => Vec2::new(CELL_SIZE + WALL_THICKNESS, WALL_THICKNESS),
+        };
+        commands.spawn(rect(theme.maze.wall, centre, size, 1.0));
+    }
+    // the outer edges aren't stored in the maze, they're always there
+    let across = Vec2::new(size.x + WALL_THICKNESS, WALL_THICKNESS);
+    let down = Vec2::new(WALL_THICKNESS, size.y + WALL_THICKNESS);
+    for (offset, size) in [
+        (Vec2::new(0.0, size.y / 2.0), across),
+        (Vec2::new(0.0, -size.y / 2.0), across),
+        (Vec2::new(-size.x / 2.0, 0.0), down),
+        (Vec2::new(size.x / 2.0, 0.0), down),
+    ] {
+        commands.spawn(rect(theme.maze.wall, middle + offset, size, 1.0));
+    }
+
+    let position = cell_centre(player_location.0);
+    commands.spawn((
+        rect(theme.maze.player, position, Vec2::splat(PLAYER_SIZE), 2.0),
+        PlayerSprite {
+            from: position,
+            to: position,
+            timer: Timer::from_seconds(GLIDE_DURATION, TimerMode::Once),
+            blocked: false,
+        },
+    ));
+    // start out looking at the player rather than gliding over from wherever the camera was
+    for mut transform in camera_query.iter_mut() {
+        transform.translation = position.extend(transform.translation.z);
+    }
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: overlay_style(&theme),
+                ..default()
+            },
+            TopDownView,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    overlay_text(maze_component, player_location.0, &objective, *seed),
+                    get_seed_text_style(&theme),
+                ),
+                TopDownLabel,
+            ));
+        });
+}
+
+fn overlay_text(
+    maze: &MazeComponent,
+    location: CellPos,
+    objective: &Objective,
+    seed: MazeSeed,
+) -> String {
+    format!(
+        "{}\nfind the way to {}\nseed: {}",
+        cell_label(maze, location),
+        cell_label(maze, objective.goal),
+        seed.0
+    )
+}
+
+pub fn update_top_down_label(
+    mut moved_reader: EventReader<PlayerMoved>,
+    objective: Res<Objective>,
+    seed: Res<MazeSeed>,
+    maze_query: Query<&MazeComponent>,
+    mut label_query: Query<&mut Text, With<TopDownLabel>>,
+) {
+    if let Some(moved) = moved_reader.iter().last() {
+        let maze = maze_query.get_single().unwrap();
+        for mut text in label_query.iter_mut() {
+            text.sections[0].value = overlay_text(maze, moved.to, &objective, *seed);
+        }
+    }
+}
+
+// moves have already been checked against the walls by move_player, so the sprite only has to
+// follow them. Walking into a wall gets a short bump against it instead
+pub fn start_glide(
+    mut moved_reader: EventReader<PlayerMoved>,
+    mut blocked_reader: EventReader<MoveBlocked>,
+    settings: Res<NavigationSettings>,
+    player_location: Res<PlayerLocation>,
+    mut player_query: Query<(&Transform, &mut PlayerSprite)>,
+) {
+    let moved = moved_reader.iter().last();
+    let blocked = blocked_reader.iter().last();
+    let Ok((transform, mut player)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    if let Some(moved) = moved {
+        // from wherever the sprite is, which may still be on the way to the last square
+        player.from = transform.translation.truncate();
+        player.to = cell_centre(moved.to);
+        player.blocked = false;
+        player.timer.reset();
+    } else if let Some(&MoveBlocked(direction)) = blocked {
+        if settings.bump_feedback && player.timer.finished() {
+            let reach = (CELL_SIZE - PLAYER_SIZE) / 2.0 - WALL_THICKNESS;
+            player.from = cell_centre(player_location.0);
+            player.to = player.from + direction_vector(direction) * reach;
+            player.blocked = true;
+            player.timer.reset();
+        }
+    }
+}
+
+pub fn animate_glide(
+    time: Res<Time>,
+    mut player_query: Query<(&mut Transform, &mut PlayerSprite)>,
+) {
+    for (mut transform, mut player) in player_query.iter_mut() {
+        player.timer.tick(time.delta());
+        let progress = player.timer.percent();
+        let along = if player.blocked {
+            // out to the wall and back again
+            (progress * PI).sin()
+        } else {
+            // smoothstep, so the player speeds up and slows down rather than jerking
+            progress * progress * (3.0 - 2.0 * progress)
+        };
+        let position = player.from.lerp(player.to, along);
+        transform.translation = position.extend(transform.translation.z);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn follow_player(
+    time: Res<Time>,
+    player_query: Query<&Transform, With<PlayerSprite>>,
+    mut camera_query: Query<&mut Transform, (With<Camera2d>, Without<PlayerSprite>)>,
+) {
+    let Ok(player) = player_query.get_single() else {
+        return;
+    };
+    // the same share of the way every second however long the frames are
+    let share = 1.0 - (-CAMERA_FOLLOW * time.delta_seconds()).exp();
+    for mut camera in camera_query.iter_mut() {
+        let target = player.translation.truncate();
+        let position = camera.translation.truncate().lerp(target, share);
+        camera.translation = position.extend(camera.translation.z);
+    }
+}
+
+// the other screens put their sprites around the middle of the window, where the camera
+// started out
+pub fn reset_camera(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    for mut camera in camera_query.iter_mut() {
+        camera.translation.x = window.width() / 2.;
+        camera.translation.y = window.height() / 2.;
+    }
+}
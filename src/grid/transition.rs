@@ -0,0 +1,288 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::background::ActiveBackground;
+use super::layout::{NavigationSettings, Page};
+use crate::styles::{main_menu_style, Theme};
+use into_the_magical_forest::maze_gen::Direction;
+
+// how the page changes when the player moves to another square
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TransitionStyle {
+    // the old page slides out and the new one slides in from the side that was moved towards
+    Slide,
+    // through black, the pages are swapped while the screen is covered
+    Fade,
+    // the new page replaces the old one straight away
+    None,
+}
+impl TransitionStyle {
+    pub fn next(self) -> Self {
+        match self {
+            TransitionStyle::Slide => TransitionStyle::Fade,
+            TransitionStyle::Fade => TransitionStyle::None,
+            TransitionStyle::None => TransitionStyle::Slide,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TransitionStyle::Slide => "slide",
+            TransitionStyle::Fade => "fade",
+            TransitionStyle::None => "none",
+        }
+    }
+}
+
+// how a transition speeds up and slows down over its duration
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Easing {
+    Linear,
+    EaseOut,
+    EaseInOut,
+}
+impl Easing {
+    // takes and gives how far along the transition is, from 0 to 1
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseOut => 1. - (1. - t) * (1. - t),
+            Easing::EaseInOut => t * t * (3. - 2. * t),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TransitionSettings {
+    pub style: TransitionStyle,
+    pub duration: Duration,
+    pub easing: Easing,
+}
+impl TransitionSettings {
+    // MAZE_TRANSITION is slide, fade or none, MAZE_TRANSITION_MS how long one takes and
+    // MAZE_TRANSITION_EASING one of linear, ease-out and ease-in-out
+    pub fn from_env() -> Self {
+        let style = match std::env::var("MAZE_TRANSITION").as_deref() {
+            Ok("fade") => TransitionStyle::Fade,
+            Ok("none") => TransitionStyle::None,
+            _ => TransitionStyle::Slide,
+        };
+        let duration = std::env::var("MAZE_TRANSITION_MS")
+            .ok()
+            .and_then(|ms| ms.trim().parse().ok())
+            .map_or(Duration::from_millis(250), Duration::from_millis);
+        let easing = match std::env::var("MAZE_TRANSITION_EASING").as_deref() {
+            Ok("linear") => Easing::Linear,
+            Ok("ease-out") => Easing::EaseOut,
+            _ => Easing::EaseInOut,
+        };
+
+        TransitionSettings {
+            style,
+            duration,
+            easing,
+        }
+    }
+
+    // whether moving should go through a transition at all
+    pub fn animated(&self) -> bool {
+        self.style != TransitionStyle::None && !self.duration.is_zero()
+    }
+}
+
+// on everything that takes part in a transition: the pages, the backgrounds and the fade cover.
+// The outgoing ones are despawned once it's over, the others stay and lose this
+#[derive(Component)]
+pub struct Transition {
+    timer: Timer,
+    style: TransitionStyle,
+    direction: Direction,
+    outgoing: bool,
+}
+impl Transition {
+    pub fn new(settings: &TransitionSettings, direction: Direction, outgoing: bool) -> Self {
+        Transition {
+            timer: Timer::new(settings.duration, TimerMode::Once),
+            style: settings.style,
+            direction,
+            outgoing,
+        }
+    }
+
+    // ticks the timer and gives how far along the transition is, eased
+    fn advance(&mut self, time: &Time, easing: Easing) -> f32 {
+        self.timer.tick(time.delta());
+        easing.apply(self.timer.percent())
+    }
+
+    fn finish(&self, commands: &mut Commands, entity: Entity) {
+        if self.outgoing {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            commands.entity(entity).remove::<Transition>();
+        }
+    }
+}
+
+// covers the screen halfway through a fade, above the pages but below the minimap
+#[derive(Component)]
+pub struct FadeCover;
+
+// moves are ignored while a transition runs, so that clicking twice can't skip a square
+pub fn no_transition(transition_query: Query<(), With<Transition>>) -> bool {
+    transition_query.is_empty()
+}
+
+// where a page is while sliding, in percent of the screen. The new page comes in from the side
+// that was moved towards
+fn slide_offset(direction: Direction, progress: f32, outgoing: bool) -> Vec2 {
+    let towards = match direction {
+        Direction::Right => Vec2::new(100., 0.),
+        Direction::Left => Vec2::new(-100., 0.),
+        Direction::Up => Vec2::new(0., -100.),
+        Direction::Down => Vec2::new(0., 100.),
+    };
+    if outgoing {
+        -towards * progress
+    } else {
+        towards * (1. - progress)
+    }
+}
+
+// both pages are taken out of the layout while sliding, so they don't push each other aside
+fn place_page(style: &mut Style, offset: Vec2) {
+    style.position_type = PositionType::Absolute;
+    style.position.left = Val::Percent(offset.x);
+    style.position.top = Val::Percent(offset.y);
+}
+
+// hands the old pages and the new one over to a transition, or despawns the old ones straight
+// away without one
+pub fn start_page_transition(
+    commands: &mut Commands,
+    theme: &Theme,
+    settings: &TransitionSettings,
+    old_pages: impl Iterator<Item = Entity>,
+    new_page: Entity,
+    direction: Direction,
+) {
+    if !settings.animated() {
+        for page_entity in old_pages {
+            commands.entity(page_entity).despawn_recursive();
+        }
+        return;
+    }
+
+    for page_entity in old_pages.chain([new_page]) {
+        let outgoing = page_entity != new_page;
+        let mut page = commands.entity(page_entity);
+        page.insert(Transition::new(settings, direction, outgoing));
+        match settings.style {
+            TransitionStyle::Slide => {
+                // placed now rather than on the next frame, so the new page never shows up
+                // in the middle
+                let mut style = main_menu_style(theme);
+                place_page(&mut style, slide_offset(direction, 0., outgoing));
+                page.insert(style);
+            }
+            TransitionStyle::Fade if !outgoing => {
+                page.insert(Visibility::Hidden);
+            }
+            _ => {}
+        }
+    }
+
+    if settings.style == TransitionStyle::Fade {
+        commands.spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                    ..default()
+                },
+                background_color: Color::NONE.into(),
+                z_index: ZIndex::Global(1),
+                ..default()
+            },
+            FadeCover,
+            Transition::new(settings, direction, true),
+        ));
+    }
+}
+
+pub fn animate_page_transitions(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<NavigationSettings>,
+    mut page_query: Query<(Entity, &mut Transition, &mut Style, &mut Visibility), With<Page>>,
+) {
+    for (entity, mut transition, mut style, mut visibility) in page_query.iter_mut() {
+        let progress = transition.advance(&time, settings.transition.easing);
+        match transition.style {
+            TransitionStyle::Slide => {
+                let offset = slide_offset(transition.direction, progress, transition.outgoing);
+                place_page(&mut style, offset);
+            }
+            // swapped while the cover hides them
+            TransitionStyle::Fade => {
+                let shown = (progress < 0.5) == transition.outgoing;
+                *visibility = if shown {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                };
+            }
+            TransitionStyle::None => {}
+        }
+
+        if transition.timer.finished() {
+            style.position_type = PositionType::Relative;
+            style.position = UiRect::DEFAULT;
+            *visibility = Visibility::Inherited;
+            transition.finish(&mut commands, entity);
+        }
+    }
+}
+
+pub fn animate_fade_cover(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<NavigationSettings>,
+    mut cover_query: Query<(Entity, &mut Transition, &mut BackgroundColor), With<FadeCover>>,
+) {
+    for (entity, mut transition, mut color) in cover_query.iter_mut() {
+        let progress = transition.advance(&time, settings.transition.easing);
+        // dark in the middle of the fade
+        color.0 = Color::rgba(0., 0., 0., 1. - (2. * progress - 1.).abs());
+
+        if transition.timer.finished() {
+            transition.finish(&mut commands, entity);
+        }
+    }
+}
+
+// the new background fades in on top of the old one, which goes once it's fully covered
+pub fn animate_background_transitions(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<NavigationSettings>,
+    mut background_query: Query<
+        (Entity, &mut Transition, &mut Sprite, &mut Transform),
+        With<ActiveBackground>,
+    >,
+) {
+    for (entity, mut transition, mut sprite, mut transform) in background_query.iter_mut() {
+        let progress = transition.advance(&time, settings.transition.easing);
+        if !transition.outgoing {
+            sprite.color.set_a(progress);
+        }
+
+        if transition.timer.finished() {
+            if !transition.outgoing {
+                transform.translation.z = 0.;
+            }
+            transition.finish(&mut commands, entity);
+        }
+    }
+}
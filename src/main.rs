@@ -1,164 +1,232 @@
-use std::{
-    thread,
-    time::{self, Duration},
-};
-
 use bevy::{
-    app::StartupSet::PostStartup,
+    ecs::system::SystemParam,
     prelude::*,
-    window::{PresentMode, PrimaryWindow, WindowMode, WindowResized},
+    window::{PresentMode, PrimaryWindow, WindowMode},
 };
 
 mod grid;
-use grid::MainMenuPlugin;
-
-mod maze_gen;
-use maze_gen::Maze;
+use grid::NavigationPlugin;
+mod menus;
+use menus::MenusPlugin;
+mod scene;
+use scene::ScenePlugin;
+mod styles;
+use styles::ThemePlugin;
+
+use into_the_magical_forest::maze_gen::{
+    generator_from_name, GenerationSteps, Loops, Maze, MazeGenerator, MazeRng, Wilsons,
+    GENERATOR_NAMES,
+};
+use rand::SeedableRng;
+use std::time::Duration;
 
-const BACKGROUND_SIZE: (f32, f32) = (1024., 800.);
+// how often the asset folders are looked through for files that were added while hot reloading
+pub const ASSET_RESCAN_INTERVAL: Duration = Duration::from_secs(2);
 
 fn main() {
+    let hot_reload = HotReload::from_env();
+
     App::new()
         .insert_resource(ClearColor(Color::rgb(0., 0., 0.)))
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Into The Magical Forest".to_string(),
-                resolution: (1200., 1000.).into(),
-                present_mode: PresentMode::AutoVsync,
-                mode: WindowMode::BorderlessFullscreen,
-                ..Default::default()
-            }),
-            ..Default::default()
-        }))
-        .add_plugin(MainMenuPlugin)
+        .add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Into The Magical Forest".to_string(),
+                        resolution: (1200., 1000.).into(),
+                        present_mode: PresentMode::AutoVsync,
+                        mode: WindowMode::BorderlessFullscreen,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .set(AssetPlugin {
+                    watch_for_changes: hot_reload.0,
+                    ..Default::default()
+                }),
+        )
+        .insert_resource(hot_reload)
+        .add_state::<AppState>()
+        .init_resource::<MazeSeed>()
+        .insert_resource(MazeAlgorithm::from_env())
+        .init_resource::<MazeSize>()
+        .init_resource::<Difficulty>()
+        .add_plugin(ThemePlugin)
+        .add_plugin(MenusPlugin)
+        .add_plugin(NavigationPlugin)
+        .add_plugin(ScenePlugin)
         .add_startup_system(spawn_camera)
-        .add_startup_system(spawn_maze)
-        // .add_startup_system(spawn_backgrounds)
-        // // make sure that the background is initialized after all of the backgrounds have spawned
-        // .add_startup_system(init_background.in_base_set(PostStartup))
-        // .add_system(maintain_background)
         .run();
 }
 
-#[derive(Component)]
-pub struct MazeComponent {
-    pub maze: Maze,
+// every screen of the game. Each state spawns its own UI when it's entered and despawns it again
+// when it's left
+#[derive(States, Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AppState {
+    #[default]
+    Title,
+    NewGame,
+    Playing,
+    Paused,
+    Victory,
+    Settings,
+    // watching the maze being generated before playing it
+    Building,
 }
 
-#[derive(Clone, Component)]
-struct Background {
-    pub image_path: String,
-    pub location: (usize, usize),
+// whether changes to the files in assets show up in the running game, for working on the
+// backgrounds, scenes and themes without restarting. On in debug builds, HOT_RELOAD=on or off
+// overrides it
+#[derive(Resource, Default, Clone, Copy)]
+pub struct HotReload(pub bool);
+impl HotReload {
+    fn from_env() -> Self {
+        match std::env::var("HOT_RELOAD").as_deref() {
+            Ok("on") => Self(true),
+            Ok("off") => Self(false),
+            _ => Self(cfg!(debug_assertions)),
+        }
+    }
 }
 
-// there should only ever be one ActiveBackground instace, the contence of the ActiveBackground
-// will change as the game changes.
-#[derive(Component)]
-struct ActiveBackground {
-    pub background: Background,
+pub fn hot_reload(hot_reload: Res<HotReload>) -> bool {
+    hot_reload.0
 }
 
-fn spawn_maze(mut commands: Commands) {
-    let maze = Maze::gen(grid::SIZE.0, grid::SIZE.1);
-    commands.spawn(MazeComponent { maze: maze.clone() });
-
-    maze.display_maze();
+#[derive(Component)]
+pub struct MazeComponent {
+    pub maze: Maze,
 }
 
-fn spawn_camera(mut commands: Commands, window_query: Query<&Window, With<PrimaryWindow>>) {
-    let window = window_query.get_single().unwrap();
-
-    commands.spawn(Camera2dBundle {
-        transform: Transform::from_xyz(window.width() / 2., window.height() / 2., 0.),
-        ..Default::default()
-    });
+// the seed the current maze was generated from. It's logged when the maze is made and shown on
+// every page, so that a reported maze can be reproduced by typing it in on the new game screen
+// or setting MAZE_SEED
+#[derive(Resource, Default, Clone, Copy)]
+pub struct MazeSeed(pub u64);
+
+// name of the generator used for the maze, one of maze_gen::GENERATOR_NAMES.
+// Set with MAZE_ALGORITHM, wilson's algorithm is used by default
+#[derive(Resource, Clone)]
+pub struct MazeAlgorithm(pub String);
+impl MazeAlgorithm {
+    fn from_env() -> Self {
+        Self(std::env::var("MAZE_ALGORITHM").unwrap_or_else(|_| String::from("wilsons")))
+    }
 }
 
-fn spawn_backgrounds(mut commands: Commands) {
-    commands.spawn(Background {
-        image_path: String::from("sprites/backgrounds/grid/9-5.png"),
-        location: (9, 5),
-    });
-    commands.spawn(Background {
-        image_path: String::from("sprites/backgrounds/grid/7-6.png"),
-        location: (7, 6),
-    });
+// in squares, picked on the new game screen
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MazeSize {
+    pub width: usize,
+    pub height: usize,
+}
+impl MazeSize {
+    pub const MIN: usize = 2;
+    pub const MAX: usize = 50;
+}
+impl Default for MazeSize {
+    fn default() -> Self {
+        Difficulty::default().size()
+    }
 }
 
-fn init_background(
-    mut commands: Commands,
-    window_query: Query<&Window, With<PrimaryWindow>>,
-    asset_server: Res<AssetServer>,
-    background_query: Query<&Background>,
-) {
-    let window = window_query.get_single().unwrap();
-    let active_background = ActiveBackground {
-        background: find_background(background_query, (9, 5)),
-    };
-
-    let scale_height = window.height() / BACKGROUND_SIZE.1;
-    let scale_width = window.width() / BACKGROUND_SIZE.0;
-
-    // find the smallest of the scales
-    let scale = if scale_width > scale_height {
-        scale_height
-    } else {
-        scale_width
-    };
-
-    commands.spawn((
-        SpriteBundle {
-            transform: Transform::from_xyz(window.width() / 2., window.height() / 2., 0.)
-                .with_scale(Vec3::splat(scale)),
-            texture: asset_server.load(&active_background.background.image_path),
-            ..Default::default()
-        },
-        active_background,
-    ));
+// presets for the new game screen. Each one picks a size, and the easier ones add loops to the
+// maze so there is more than one way through it
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
 }
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
 
-// the size of the background should change as the window is resized to fit the greatest size it
-// can on the screen while still maintaining the original aspect ratio.
-fn maintain_background(
-    resize_event: Res<Events<WindowResized>>,
-    mut active_background_query: Query<&mut Transform, With<ActiveBackground>>,
-) {
-    let mut reader = resize_event.get_reader();
-    for e in reader.iter(&resize_event) {
-        let scale_height = e.height / BACKGROUND_SIZE.1;
-        let scale_width = e.width / BACKGROUND_SIZE.0;
-
-        // find the smallest of the scales
-        let scale = if scale_width > scale_height {
-            scale_height
-        } else {
-            scale_width
+    pub fn size(&self) -> MazeSize {
+        let (width, height) = match self {
+            Difficulty::Easy => (6, 6),
+            Difficulty::Normal => (9, 9),
+            Difficulty::Hard => (16, 12),
         };
+        MazeSize { width, height }
+    }
 
-        let mut transform = active_background_query.get_single_mut().unwrap();
-
-        *transform = transform.with_scale(Vec3::splat(scale));
+    pub fn loops(&self) -> Option<Loops> {
+        match self {
+            Difficulty::Easy => Some(Loops::Braid(0.5)),
+            Difficulty::Normal | Difficulty::Hard => None,
+        }
     }
 }
 
-fn find_background(background_query: Query<&Background>, location: (usize, usize)) -> Background {
-    background_query
-        .iter()
-        .find(|b| b.location == location)
-        .expect("Could not find background that matched location")
-        .clone()
+// everything that decides what the next maze looks like
+#[derive(SystemParam)]
+pub struct MazeOptions<'w> {
+    pub seed: ResMut<'w, MazeSeed>,
+    pub algorithm: Res<'w, MazeAlgorithm>,
+    pub size: Res<'w, MazeSize>,
+    pub difficulty: Res<'w, Difficulty>,
 }
+impl MazeOptions<'_> {
+    pub fn generate(&self) -> Maze {
+        let generator = self.generator();
+        info!(
+            "generating a {}x{} {:?} maze with seed {} using {}",
+            self.size.width,
+            self.size.height,
+            *self.difficulty,
+            self.seed.0,
+            generator.name()
+        );
+        let mut maze = Maze::gen_with(
+            generator.as_ref(),
+            self.size.height,
+            self.size.width,
+            self.seed.0,
+        );
+        if let Some(loops) = self.difficulty.loops() {
+            maze.add_loops(loops, &mut MazeRng::seed_from_u64(self.seed.0));
+        }
+        maze
+    }
+
+    // the steps of generating the same maze as generate, without the loops the difficulty adds
+    // afterwards
+    pub fn steps(&self) -> GenerationSteps {
+        Maze::steps_with(
+            self.generator().as_ref(),
+            self.size.height,
+            self.size.width,
+            self.seed.0,
+        )
+    }
+
+    fn generator(&self) -> Box<dyn MazeGenerator> {
+        generator_from_name(&self.algorithm.0).unwrap_or_else(|| {
+            warn!(
+                "unknown maze algorithm {:?}, expected one of {:?}. Falling back to wilsons",
+                self.algorithm.0, GENERATOR_NAMES
+            );
+            Box::new(Wilsons)
+        })
+    }
+}
+
+fn spawn_camera(mut commands: Commands, window_query: Query<&Window, With<PrimaryWindow>>) {
+    let window = window_query.get_single().unwrap();
 
-fn change_background(
-    asset_server: Res<AssetServer>,
-    mut active_background_query: Query<&mut Handle<Image>, With<ActiveBackground>>,
-    new_background_query: Query<&Background>,
-    new_background: (usize, usize),
-) {
-    // this should be safe to unwrap because there is only one ActiveBackground instance in the
-    // program
-    let mut background = active_background_query.get_single_mut().unwrap();
-
-    *background =
-        asset_server.load(&find_background(new_background_query, new_background).image_path);
+    // the bundle puts the camera far enough back to see sprites with a z of 0 up to almost its far
+    // plane, so only x and y are moved
+    let mut camera = Camera2dBundle::default();
+    camera.transform.translation.x = window.width() / 2.;
+    camera.transform.translation.y = window.height() / 2.;
+    commands.spawn(camera);
 }
@@ -1,260 +1,336 @@
-use std::io::{self, Write};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 
-use rand::{self, Rng};
+mod algorithms;
+mod braid;
+mod cell;
+mod image;
+mod parse;
+mod serialize;
+mod solver;
+mod steps;
+mod text;
+mod walls;
+pub use algorithms::{
+    AldousBroder, BinaryTree, Ellers, GrowingTree, HuntAndKill, Kruskals, Prims,
+    RecursiveBacktracker, Sidewinder,
+};
+pub use braid::Loops;
+pub use cell::{CellPos, Direction};
+pub use image::{ImageOverlays, ImageStyle, Rgb};
+pub use parse::ParseMazeError;
+pub use serialize::{MazeFormat, MazeFormatError, FORMAT_VERSION};
+pub use steps::{GenerationEvent, GenerationSteps};
+pub use text::{TextOverlays, TextStyle};
+pub use walls::NestedWalls;
 
-#[derive(Debug, Clone, Copy)]
-enum SquareState {
-    None,
-    Taken,
+// the rng every generator runs through. ChaCha8 is used rather than StdRng because its output
+// is specified, so the same seed gives the same maze on every platform and rand version
+pub type MazeRng = ChaCha8Rng;
 
-    Left,
-    Right,
-    Up,
-    Down,
+// every algorithm produces a perfect maze, but they differ a lot in texture. Recursive
+// backtracker gives long winding corridors while prim's and kruskal's give lots of short dead
+// ends, so the algorithm is something a level can choose
+pub trait MazeGenerator {
+    fn name(&self) -> &'static str;
+    fn generate(&self, height: usize, width: usize, rng: &mut dyn RngCore) -> Maze;
+
+    // the same maze as generate, built up one GenerationEvent at a time for watching it happen.
+    // All of the generators here record their own steps. For any other one this generates the
+    // whole maze and then replays it
+    fn steps(&self, height: usize, width: usize, mut rng: MazeRng) -> GenerationSteps {
+        steps::replay(self.generate(height, width, &mut rng))
+    }
+}
+
+pub struct Wilsons;
+impl MazeGenerator for Wilsons {
+    fn name(&self) -> &'static str {
+        "wilsons"
+    }
+
+    fn generate(&self, height: usize, width: usize, rng: &mut dyn RngCore) -> Maze {
+        wilsons_algorithm(height, width, rng)
+    }
+
+    fn steps(&self, height: usize, width: usize, rng: MazeRng) -> GenerationSteps {
+        Box::new(steps::WilsonsSteps::new(height, width, rng))
+    }
+}
+
+pub const GENERATOR_NAMES: [&str; 10] = [
+    "wilsons",
+    "recursive-backtracker",
+    "kruskals",
+    "prims",
+    "ellers",
+    "aldous-broder",
+    "hunt-and-kill",
+    "binary-tree",
+    "sidewinder",
+    "growing-tree",
+];
+
+// the entry in GENERATOR_NAMES a name refers to. Case and the choice between '-', '_' and ' '
+// don't matter so that names can come straight from level files
+pub fn generator_name(name: &str) -> Option<&'static str> {
+    let name = name.trim().to_lowercase().replace(['_', ' '], "-");
+    GENERATOR_NAMES.into_iter().find(|&known| known == name)
+}
+
+// look up a generator by one of the names in GENERATOR_NAMES, see generator_name
+pub fn generator_from_name(name: &str) -> Option<Box<dyn MazeGenerator>> {
+    let generator: Box<dyn MazeGenerator> = match generator_name(name)? {
+        "wilsons" => Box::new(Wilsons),
+        "recursive-backtracker" => Box::new(RecursiveBacktracker),
+        "kruskals" => Box::new(Kruskals),
+        "prims" => Box::new(Prims),
+        "ellers" => Box::new(Ellers),
+        "aldous-broder" => Box::new(AldousBroder),
+        "hunt-and-kill" => Box::new(HuntAndKill),
+        "binary-tree" => Box::new(BinaryTree),
+        "sidewinder" => Box::new(Sidewinder),
+        "growing-tree" => Box::new(GrowingTree::default()),
+        _ => return None,
+    };
+    Some(generator)
 }
 
-// each wall will be represented by a boolean.
-// Either the path is blocked, or it isn't, pretty simple
-#[derive(Clone)]
+// each wall will be represented by a single bit, see the walls module.
+// Either the path is blocked, or it isn't, pretty simple.
+// Serialized through MazeFile so that saved mazes carry a version and get validated on load
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "serialize::MazeFile", try_from = "serialize::MazeFile")]
 pub struct Maze {
-    pub walls: (Vec<Vec<bool>>, Vec<Vec<bool>>),
+    height: usize,
+    width: usize,
+    bits: Vec<u64>,
 }
 impl Maze {
     pub fn gen(height: usize, width: usize) -> Self {
-        wilsons_algorithm(height, width)
+        Self::from_seed(height, width, rand::random())
     }
 
-    pub fn display_maze(&self) {
-        let height = self.walls.0.len();
-        let width = self.walls.1[0].len();
-
-        // print top wall
-        // there should be a gap in the top left corner
-        print!(" ");
-        for _ in 0..width - 1 {
-            print!("___");
-        }
-        // the last one should leave a gap in the corner
-        print!("__\n");
-        io::stdout()
-            .flush()
-            .expect("Could not display Maze instance correctly");
-
-        for h in 0..height {
-            print!("|");
-            for w in 0..width {
-                // for the horizontal walls
-                if h < height - 1 {
-                    if self.walls.1[h][w] {
-                        print!("__");
-                    } else {
-                        print!("  ");
-                    }
-                } else {
-                    // lowest layer should be filled in
-                    print!("__");
-                }
+    pub fn from_seed(height: usize, width: usize, seed: u64) -> Self {
+        Self::gen_with_rng(height, width, &mut MazeRng::seed_from_u64(seed))
+    }
 
-                // for the vertical walls
-                if w < width - 1 {
-                    if self.walls.0[h][w] {
-                        print!("|");
-                    } else if (h < height - 1 && self.walls.1[h][w] && self.walls.1[h][w + 1])
-                        || h == height - 1
-                    {
-                        // if both of the surrounding horizontal lines are filled in,
-                        // it looks a bit strange if there's a gap between them
-                        // this also applies if we're at the lowest level
-                        print!("_");
-                    } else {
-                        print!(" ");
-                    }
-                }
-            }
-            print!("|\n");
-            io::stdout()
-                .flush()
-                .expect("Could not display Maze instance correctly");
-        }
+    pub fn gen_with_rng<R: Rng + ?Sized>(height: usize, width: usize, rng: &mut R) -> Self {
+        wilsons_algorithm(height, width, rng)
     }
-}
 
-fn wilsons_algorithm(height: usize, width: usize) -> Maze {
-    let mut grid: Vec<Vec<SquareState>> = vec![vec![SquareState::None; width]; height];
-    let mut walls_horizontal: Vec<Vec<bool>> = vec![vec![true; width - 1]; height];
-    let mut walls_vertical: Vec<Vec<bool>> = vec![vec![true; width]; height - 1];
-
-    // starting square
-    let starting_square = select_square(height, width, &grid);
-    grid[starting_square.0][starting_square.1] = SquareState::Taken;
-
-    // iterating over the grid to fill up the squares that are not already Taken
-    while !is_complete(&grid) {
-        fill_grid(
-            &mut grid,
-            (&mut walls_horizontal, &mut walls_vertical),
-            height,
-            width,
-        );
+    pub fn gen_with(generator: &dyn MazeGenerator, height: usize, width: usize, seed: u64) -> Self {
+        generator.generate(height, width, &mut MazeRng::seed_from_u64(seed))
     }
 
-    Maze {
-        walls: (walls_horizontal, walls_vertical),
+    pub fn height(&self) -> usize {
+        self.height
     }
-}
 
-fn is_complete(grid: &Vec<Vec<SquareState>>) -> bool {
-    for line in grid {
-        for square in line {
-            if let SquareState::None = square {
-                return false;
-            }
-        }
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    // remove the wall between two neighbouring squares, given as (row, column)
+    fn carve(&mut self, a: (usize, usize), b: (usize, usize)) {
+        let (a, b) = (CellPos::from(a), CellPos::from(b));
+        // the generators only ever carve between neighbours
+        let Some(direction) = Direction::between(a, b) else {
+            unreachable!("{a:?} and {b:?} are not next to each other");
+        };
+        self.set_wall(a, direction, false);
     }
-    // if the loop passes, the maze is complete
-    true
-}
 
-// select a square to start the iteration from
-fn select_square(height: usize, width: usize, grid: &Vec<Vec<SquareState>>) -> (usize, usize) {
-    let square = (
-        rand::thread_rng().gen_range(0..height),
-        rand::thread_rng().gen_range(0..width),
-    );
-
-    // check to see if the square is taken
-    if let SquareState::Taken = grid[square.0][square.1] {
-        // if it is, then pick again
-        select_square(height, width, grid)
-    } else {
-        square
+    // prints the underscore drawing, see the text module for the other styles
+    pub fn display_maze(&self) {
+        print!(
+            "{}",
+            self.render_text(TextStyle::Underscore, &TextOverlays::default())
+        );
     }
 }
 
-// select direction for random walk
-fn select_direction(height: usize, width: usize, current_position: &(usize, usize)) -> SquareState {
-    let rand_numb = rand::thread_rng().gen_range(1..=4);
-
-    // check to make sure that we can move in the chosen direction
-    if (current_position.1 == 0 && rand_numb == 1) // left
-        || (current_position.1 == width - 1 && rand_numb == 2) // right
-        || (current_position.0 == 0 && rand_numb == 3) // up
-        || (current_position.0 == height - 1 && rand_numb == 4)
-    // down
-    {
-        // pick again
-        select_direction(height, width, current_position)
-    } else {
-        match rand_numb {
-            1 => SquareState::Left,
-            2 => SquareState::Right,
-            3 => SquareState::Up,
-            4 => SquareState::Down,
-            // because the random number is generated on a range between 1 and 4, there is no way for
-            // rand_numb to take on any other value
-            _ => unreachable!(),
+// usize is sampled differently on 32 and 64 bit targets, so indices are always drawn as u32 to
+// keep seeded mazes identical everywhere
+fn gen_index<R: Rng + ?Sized>(rng: &mut R, upper: usize) -> usize {
+    rng.gen_range(0..upper as u32) as usize
+}
+
+// a set of squares that can be picked from at random and taken out of in constant time, so that
+// finding a square that isn't part of the maze yet doesn't mean scanning the grid
+struct IndexedSet {
+    items: Vec<u32>,
+    // where each square is in items, u32::MAX once it has been taken out
+    positions: Vec<u32>,
+}
+impl IndexedSet {
+    fn full(len: usize) -> Self {
+        IndexedSet {
+            items: (0..len as u32).collect(),
+            positions: (0..len as u32).collect(),
         }
     }
-}
 
-fn fill_grid(
-    grid: &mut Vec<Vec<SquareState>>,
-    walls: (&mut Vec<Vec<bool>>, &mut Vec<Vec<bool>>),
-    height: usize,
-    width: usize,
-) {
-    let starting_position = select_square(height, width, grid);
-    let mut current_position = starting_position;
-
-    // creating a path for this section of the maze
-    loop {
-        let direction = select_direction(height, width, &current_position);
-        // set the direction of the current position
-        grid[current_position.0][current_position.1] = direction;
-
-        // set new current position
-        match direction {
-            SquareState::Right => {
-                current_position.1 += 1;
-            }
-            SquareState::Left => {
-                current_position.1 -= 1;
-            }
-            SquareState::Up => {
-                current_position.0 -= 1;
-            }
-            SquareState::Down => {
-                current_position.0 += 1;
-            }
-            // only the direction versions of the SquareState enum can be returned by the
-            // select_direction function
-            _ => unreachable!(),
+    fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    fn pick<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        self.items[gen_index(rng, self.items.len())] as usize
+    }
+
+    fn remove(&mut self, item: usize) {
+        let position = self.positions[item];
+        if position == u32::MAX {
+            return;
         }
-        if let SquareState::Taken = grid[current_position.0][current_position.1] {
-            break;
-        } else {
-            continue;
+        let last = *self.items.last().unwrap();
+        self.items.swap_remove(position as usize);
+        if last as usize != item {
+            self.positions[last as usize] = position;
         }
+        self.positions[item] = u32::MAX;
+    }
+}
+
+// loop erased random walks: start from a random square outside the maze and walk randomly until
+// the walk hits the maze. Whenever the walk crosses itself the loop it just made is erased, and
+// once it hits the maze what's left of it is carved in. This gives every possible maze the same
+// chance of coming up, without the bias the faster algorithms have
+fn wilsons_algorithm<R: Rng + ?Sized>(height: usize, width: usize, rng: &mut R) -> Maze {
+    let mut maze = Maze::closed(height, width);
+
+    // JOINED for squares already carved, otherwise the direction the current walk last left the
+    // square by, a byte each. A border of OFF_EDGE goes all the way round so that a step only
+    // has to look at where it lands to know whether it went off the edge
+    let padded_width = width + 2;
+    let padded = |index: usize| (index / width + 1) * padded_width + index % width + 1;
+    let mut exits = vec![OFF_EDGE; (height + 2) * padded_width];
+    for row in 1..=height {
+        exits[row * padded_width + 1..][..width].fill(0);
     }
+    // how far a step in each of Direction::ALL moves in exits
+    let offsets = [
+        padded_width.wrapping_neg(),
+        1,
+        padded_width,
+        1usize.wrapping_neg(),
+    ];
+
+    let mut outside = IndexedSet::full(height * width);
+    let mut directions = RandomDirections::default();
 
-    current_position = starting_position;
-
-    // solidify the path and set out walls
-    loop {
-        match grid[current_position.0][current_position.1] {
-            SquareState::Right => {
-                walls.0[current_position.0][current_position.1] = false;
-                grid[current_position.0][current_position.1] = SquareState::Taken;
-                current_position.1 += 1;
-            }
-            SquareState::Left => {
-                walls.0[current_position.0][current_position.1 - 1] = false;
-                grid[current_position.0][current_position.1] = SquareState::Taken;
-                current_position.1 -= 1;
-            }
-            SquareState::Down => {
-                walls.1[current_position.0][current_position.1] = false;
-                grid[current_position.0][current_position.1] = SquareState::Taken;
-                current_position.0 += 1;
-            }
-            SquareState::Up => {
-                walls.1[current_position.0 - 1][current_position.1] = false;
-                grid[current_position.0][current_position.1] = SquareState::Taken;
-                current_position.0 -= 1;
-            }
-            // we only need to cover the directions, there is no way for the current position to be
-            // Taken or None
-            _ => unreachable!(),
+    let first = outside.pick(rng);
+    outside.remove(first);
+    exits[padded(first)] = JOINED;
+
+    while !outside.is_empty() {
+        let start = padded(outside.pick(rng));
+
+        // a square's last exit overwrites the earlier ones, which is what erases the loops
+        let mut square = start;
+        while exits[square] != JOINED {
+            let (direction, next) = loop {
+                let direction = directions.next(rng) as usize;
+                let next = square.wrapping_add(offsets[direction]);
+                if exits[next] != OFF_EDGE {
+                    break (direction, next);
+                }
+            };
+            exits[square] = direction as u8;
+            square = next;
         }
 
-        if let SquareState::Taken = grid[current_position.0][current_position.1] {
-            break;
-        } else {
-            continue;
+        // following the last exits from the start gives the walk without its loops, carve it in
+        let mut square = start;
+        while exits[square] != JOINED {
+            let direction = exits[square] as usize;
+            exits[square] = JOINED;
+            let cell = CellPos::new(square / padded_width - 1, square % padded_width - 1);
+            outside.remove(cell.row * width + cell.col);
+            maze.set_wall(cell, Direction::ALL[direction], false);
+            square = square.wrapping_add(offsets[direction]);
         }
     }
 
-    for line in &mut *grid {
-        for square in line {
-            match square {
-                SquareState::Taken => {}
-                SquareState::None => {}
-                _ => *square = SquareState::None,
-            }
+    maze
+}
+
+// the exits map of wilson's algorithm
+const JOINED: u8 = u8::MAX;
+const OFF_EDGE: u8 = u8::MAX - 1;
+
+// where a random walk is, as the index of the square as well as its row and column so that the
+// edges can be checked without dividing on every step
+#[derive(Clone, Copy)]
+struct WalkPosition {
+    index: usize,
+    row: usize,
+    col: usize,
+}
+impl WalkPosition {
+    fn new(index: usize, width: usize) -> Self {
+        WalkPosition {
+            index,
+            row: index / width,
+            col: index % width,
         }
     }
 }
 
-// strictly for testing purposes
-#[allow(dead_code)]
-fn display_grid(grid: &Vec<Vec<SquareState>>) {
-    for line in grid {
-        for square in line {
-            print!("{square:?}\t\t");
+// the walks take a lot of steps, so rather than drawing a whole number for each one the
+// directions are taken two bits at a time out of a u32
+#[derive(Default)]
+struct RandomDirections {
+    bits: u32,
+    left: u32,
+}
+impl RandomDirections {
+    fn next<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Direction {
+        if self.left == 0 {
+            self.bits = rng.next_u32();
+            self.left = 16;
+        }
+        let direction = Direction::ALL[(self.bits & 3) as usize];
+        self.bits >>= 2;
+        self.left -= 1;
+        direction
+    }
+    // one step of a random walk. Directions that lead off the edge are drawn again, every square
+    // has at least one neighbour inside the maze so this never takes long
+    fn step<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        height: usize,
+        width: usize,
+        from: WalkPosition,
+    ) -> WalkPosition {
+        let WalkPosition { index, row, col } = from;
+        loop {
+            return match self.next(rng) {
+                Direction::Up if row > 0 => WalkPosition {
+                    index: index - width,
+                    row: row - 1,
+                    col,
+                },
+                Direction::Right if col + 1 < width => WalkPosition {
+                    index: index + 1,
+                    row,
+                    col: col + 1,
+                },
+                Direction::Down if row + 1 < height => WalkPosition {
+                    index: index + width,
+                    row: row + 1,
+                    col,
+                },
+                Direction::Left if col > 0 => WalkPosition {
+                    index: index - 1,
+                    row,
+                    col: col - 1,
+                },
+                _ => continue,
+            };
         }
-        print!("\n");
-        io::stdout()
-            .flush()
-            .expect("Could not display the grid correctly");
     }
 }
@@ -0,0 +1,509 @@
+use rand::{Rng, RngCore};
+
+use super::steps::{built, recorded, MazeBuilder};
+use super::{gen_index, CellPos, Direction, GenerationSteps, Maze, MazeGenerator, MazeRng};
+
+// all of the squares next to the given one that are inside the grid.
+// The order is fixed (up, right, down, left) so that seeded mazes stay reproducible
+fn neighbours(height: usize, width: usize, square: (usize, usize)) -> Vec<(usize, usize)> {
+    Direction::ALL
+        .into_iter()
+        .filter_map(|direction| CellPos::from(square).step(direction))
+        .filter(|next| next.row < height && next.col < width)
+        .map(<(usize, usize)>::from)
+        .collect()
+}
+
+// SliceRandom::shuffle samples usize, so a plain Fisher-Yates on top of gen_index is used instead
+pub(super) fn shuffle<T, R: Rng + ?Sized>(items: &mut [T], rng: &mut R) {
+    for i in (1..items.len()).rev() {
+        items.swap(i, gen_index(rng, i + 1));
+    }
+}
+
+fn random_square(height: usize, width: usize, rng: &mut dyn RngCore) -> (usize, usize) {
+    (gen_index(rng, height), gen_index(rng, width))
+}
+
+// every wall inside the grid, given as the pair of squares it separates
+fn inner_walls(height: usize, width: usize) -> Vec<((usize, usize), (usize, usize))> {
+    let mut walls = Vec::new();
+    for row in 0..height {
+        for col in 0..width {
+            if col < width - 1 {
+                walls.push(((row, col), (row, col + 1)));
+            }
+            if row < height - 1 {
+                walls.push(((row, col), (row + 1, col)));
+            }
+        }
+    }
+    walls
+}
+
+// depth first search that backs up whenever it walks into a dead end.
+// Gives few, very long corridors
+pub struct RecursiveBacktracker;
+impl MazeGenerator for RecursiveBacktracker {
+    fn name(&self) -> &'static str {
+        "recursive-backtracker"
+    }
+
+    fn generate(&self, height: usize, width: usize, rng: &mut dyn RngCore) -> Maze {
+        built(height, width, |maze| {
+            recursive_backtracker(maze, height, width, rng)
+        })
+    }
+
+    fn steps(&self, height: usize, width: usize, mut rng: MazeRng) -> GenerationSteps {
+        recorded(height, width, |maze| {
+            recursive_backtracker(maze, height, width, &mut rng)
+        })
+    }
+}
+
+fn recursive_backtracker(
+    maze: &mut dyn MazeBuilder,
+    height: usize,
+    width: usize,
+    rng: &mut dyn RngCore,
+) {
+    let mut visited = vec![vec![false; width]; height];
+
+    // an explicit stack is used rather than actual recursion so big mazes can't overflow
+    let start = random_square(height, width, rng);
+    maze.start(start);
+    visited[start.0][start.1] = true;
+    let mut stack = vec![start];
+
+    while let Some(&current) = stack.last() {
+        let unvisited: Vec<(usize, usize)> = neighbours(height, width, current)
+            .into_iter()
+            .filter(|n| !visited[n.0][n.1])
+            .collect();
+
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+
+        let next = unvisited[gen_index(rng, unvisited.len())];
+        maze.carve(current, next);
+        visited[next.0][next.1] = true;
+        stack.push(next);
+    }
+}
+
+// knock down walls in a random order, as long as they separate two squares that aren't yet
+// connected
+pub struct Kruskals;
+impl MazeGenerator for Kruskals {
+    fn name(&self) -> &'static str {
+        "kruskals"
+    }
+
+    fn generate(&self, height: usize, width: usize, rng: &mut dyn RngCore) -> Maze {
+        built(height, width, |maze| kruskals(maze, height, width, rng))
+    }
+
+    fn steps(&self, height: usize, width: usize, mut rng: MazeRng) -> GenerationSteps {
+        recorded(height, width, |maze| {
+            kruskals(maze, height, width, &mut rng)
+        })
+    }
+}
+
+fn kruskals(maze: &mut dyn MazeBuilder, height: usize, width: usize, rng: &mut dyn RngCore) {
+    let mut walls = inner_walls(height, width);
+    shuffle(&mut walls, rng);
+
+    // union-find over the squares, indexed row by row
+    let mut parents: Vec<usize> = (0..height * width).collect();
+    fn find(parents: &mut [usize], mut i: usize) -> usize {
+        while parents[i] != i {
+            parents[i] = parents[parents[i]];
+            i = parents[i];
+        }
+        i
+    }
+
+    for (a, b) in walls {
+        let set_a = find(&mut parents, a.0 * width + a.1);
+        let set_b = find(&mut parents, b.0 * width + b.1);
+        if set_a != set_b {
+            parents[set_a] = set_b;
+            maze.carve(a, b);
+        }
+    }
+}
+
+// grow the maze outwards from a single square by picking a random wall on its edge each time
+pub struct Prims;
+impl MazeGenerator for Prims {
+    fn name(&self) -> &'static str {
+        "prims"
+    }
+
+    fn generate(&self, height: usize, width: usize, rng: &mut dyn RngCore) -> Maze {
+        built(height, width, |maze| prims(maze, height, width, rng))
+    }
+
+    fn steps(&self, height: usize, width: usize, mut rng: MazeRng) -> GenerationSteps {
+        recorded(height, width, |maze| prims(maze, height, width, &mut rng))
+    }
+}
+
+fn prims(maze: &mut dyn MazeBuilder, height: usize, width: usize, rng: &mut dyn RngCore) {
+    let mut in_maze = vec![vec![false; width]; height];
+
+    let start = random_square(height, width, rng);
+    maze.start(start);
+    in_maze[start.0][start.1] = true;
+    let mut frontier: Vec<((usize, usize), (usize, usize))> = neighbours(height, width, start)
+        .into_iter()
+        .map(|n| (start, n))
+        .collect();
+
+    while !frontier.is_empty() {
+        let (from, to) = frontier.swap_remove(gen_index(rng, frontier.len()));
+        if in_maze[to.0][to.1] {
+            continue;
+        }
+
+        maze.carve(from, to);
+        in_maze[to.0][to.1] = true;
+        for n in neighbours(height, width, to) {
+            if !in_maze[n.0][n.1] {
+                frontier.push((to, n));
+            }
+        }
+    }
+}
+
+// works one row at a time, only ever keeping track of which set each square in the current row
+// belongs to
+pub struct Ellers;
+impl MazeGenerator for Ellers {
+    fn name(&self) -> &'static str {
+        "ellers"
+    }
+
+    fn generate(&self, height: usize, width: usize, rng: &mut dyn RngCore) -> Maze {
+        built(height, width, |maze| ellers(maze, height, width, rng))
+    }
+
+    fn steps(&self, height: usize, width: usize, mut rng: MazeRng) -> GenerationSteps {
+        recorded(height, width, |maze| ellers(maze, height, width, &mut rng))
+    }
+}
+
+fn ellers(maze: &mut dyn MazeBuilder, height: usize, width: usize, rng: &mut dyn RngCore) {
+    let mut sets: Vec<usize> = (0..width).collect();
+    let mut next_set = width;
+
+    for row in 0..height {
+        let last_row = row == height - 1;
+
+        // randomly join neighbours that are not yet connected. On the last row every
+        // neighbouring pair in different sets has to be joined to finish the maze
+        for col in 0..width - 1 {
+            if sets[col] != sets[col + 1] && (last_row || rng.gen_bool(0.5)) {
+                let (keep, replace) = (sets[col], sets[col + 1]);
+                for set in sets.iter_mut() {
+                    if *set == replace {
+                        *set = keep;
+                    }
+                }
+                maze.carve((row, col), (row, col + 1));
+            }
+        }
+
+        if last_row {
+            break;
+        }
+
+        // every set needs at least one passage down, or it would be cut off from the rest
+        let mut groups: Vec<(usize, Vec<usize>)> = Vec::new();
+        for (col, &set) in sets.iter().enumerate() {
+            match groups.iter_mut().find(|(s, _)| *s == set) {
+                Some((_, cols)) => cols.push(col),
+                None => groups.push((set, vec![col])),
+            }
+        }
+
+        let mut next_sets: Vec<Option<usize>> = vec![None; width];
+        for (set, cols) in groups {
+            let forced = cols[gen_index(rng, cols.len())];
+            for col in cols {
+                if col == forced || rng.gen_bool(0.5) {
+                    maze.carve((row, col), (row + 1, col));
+                    next_sets[col] = Some(set);
+                }
+            }
+        }
+
+        // squares that weren't joined from above start out in a set of their own
+        for (col, set) in next_sets.into_iter().enumerate() {
+            sets[col] = set.unwrap_or_else(|| {
+                next_set += 1;
+                next_set
+            });
+        }
+    }
+}
+
+// a pure random walk that carves whenever it steps onto a new square.
+// Unbiased like wilson's, but slow to finish on large grids
+pub struct AldousBroder;
+impl MazeGenerator for AldousBroder {
+    fn name(&self) -> &'static str {
+        "aldous-broder"
+    }
+
+    fn generate(&self, height: usize, width: usize, rng: &mut dyn RngCore) -> Maze {
+        built(height, width, |maze| {
+            aldous_broder(maze, height, width, rng)
+        })
+    }
+
+    fn steps(&self, height: usize, width: usize, mut rng: MazeRng) -> GenerationSteps {
+        recorded(height, width, |maze| {
+            aldous_broder(maze, height, width, &mut rng)
+        })
+    }
+}
+
+fn aldous_broder(maze: &mut dyn MazeBuilder, height: usize, width: usize, rng: &mut dyn RngCore) {
+    let mut visited = vec![vec![false; width]; height];
+
+    let mut current = random_square(height, width, rng);
+    maze.start(current);
+    visited[current.0][current.1] = true;
+    let mut remaining = height * width - 1;
+
+    while remaining > 0 {
+        let options = neighbours(height, width, current);
+        let next = options[gen_index(rng, options.len())];
+        if !visited[next.0][next.1] {
+            maze.carve(current, next);
+            visited[next.0][next.1] = true;
+            remaining -= 1;
+        }
+        current = next;
+    }
+}
+
+// random walk until stuck, then scan for an unvisited square next to the finished part of the
+// maze and carry on from there
+pub struct HuntAndKill;
+impl MazeGenerator for HuntAndKill {
+    fn name(&self) -> &'static str {
+        "hunt-and-kill"
+    }
+
+    fn generate(&self, height: usize, width: usize, rng: &mut dyn RngCore) -> Maze {
+        built(height, width, |maze| {
+            hunt_and_kill(maze, height, width, rng)
+        })
+    }
+
+    fn steps(&self, height: usize, width: usize, mut rng: MazeRng) -> GenerationSteps {
+        recorded(height, width, |maze| {
+            hunt_and_kill(maze, height, width, &mut rng)
+        })
+    }
+}
+
+fn hunt_and_kill(maze: &mut dyn MazeBuilder, height: usize, width: usize, rng: &mut dyn RngCore) {
+    let mut visited = vec![vec![false; width]; height];
+
+    let mut current = Some(random_square(height, width, rng));
+    if let Some(start) = current {
+        maze.start(start);
+        visited[start.0][start.1] = true;
+    }
+
+    while let Some(square) = current {
+        // kill: walk to random unvisited neighbours
+        let unvisited: Vec<(usize, usize)> = neighbours(height, width, square)
+            .into_iter()
+            .filter(|n| !visited[n.0][n.1])
+            .collect();
+
+        if !unvisited.is_empty() {
+            let next = unvisited[gen_index(rng, unvisited.len())];
+            maze.carve(square, next);
+            visited[next.0][next.1] = true;
+            current = Some(next);
+            continue;
+        }
+
+        // hunt: find the first unvisited square that borders the maze
+        current = None;
+        'hunt: for row in 0..height {
+            for col in 0..width {
+                if visited[row][col] {
+                    continue;
+                }
+                let joined: Vec<(usize, usize)> = neighbours(height, width, (row, col))
+                    .into_iter()
+                    .filter(|n| visited[n.0][n.1])
+                    .collect();
+                if !joined.is_empty() {
+                    let link = joined[gen_index(rng, joined.len())];
+                    maze.carve((row, col), link);
+                    visited[row][col] = true;
+                    current = Some((row, col));
+                    break 'hunt;
+                }
+            }
+        }
+    }
+}
+
+// every square opens either up or to the right. Very fast but leaves a straight corridor along
+// the top row and the right column
+pub struct BinaryTree;
+impl MazeGenerator for BinaryTree {
+    fn name(&self) -> &'static str {
+        "binary-tree"
+    }
+
+    fn generate(&self, height: usize, width: usize, rng: &mut dyn RngCore) -> Maze {
+        built(height, width, |maze| binary_tree(maze, height, width, rng))
+    }
+
+    fn steps(&self, height: usize, width: usize, mut rng: MazeRng) -> GenerationSteps {
+        recorded(height, width, |maze| {
+            binary_tree(maze, height, width, &mut rng)
+        })
+    }
+}
+
+fn binary_tree(maze: &mut dyn MazeBuilder, height: usize, width: usize, rng: &mut dyn RngCore) {
+    for row in 0..height {
+        for col in 0..width {
+            let can_go_up = row > 0;
+            let can_go_right = col < width - 1;
+
+            if can_go_up && (!can_go_right || rng.gen_bool(0.5)) {
+                maze.carve((row, col), (row - 1, col));
+            } else if can_go_right {
+                maze.carve((row, col), (row, col + 1));
+            }
+        }
+    }
+}
+
+// builds runs of squares along each row and opens one square of every run upwards.
+// Like the binary tree, the top row ends up as one long corridor
+pub struct Sidewinder;
+impl MazeGenerator for Sidewinder {
+    fn name(&self) -> &'static str {
+        "sidewinder"
+    }
+
+    fn generate(&self, height: usize, width: usize, rng: &mut dyn RngCore) -> Maze {
+        built(height, width, |maze| sidewinder(maze, height, width, rng))
+    }
+
+    fn steps(&self, height: usize, width: usize, mut rng: MazeRng) -> GenerationSteps {
+        recorded(height, width, |maze| {
+            sidewinder(maze, height, width, &mut rng)
+        })
+    }
+}
+
+fn sidewinder(maze: &mut dyn MazeBuilder, height: usize, width: usize, rng: &mut dyn RngCore) {
+    for row in 0..height {
+        let mut run_start = 0;
+        for col in 0..width {
+            let at_right_edge = col == width - 1;
+            let close_run = at_right_edge || (row > 0 && rng.gen_bool(0.5));
+
+            if close_run {
+                if row > 0 {
+                    let up_from = run_start + gen_index(rng, col - run_start + 1);
+                    maze.carve((row, up_from), (row - 1, up_from));
+                }
+                run_start = col + 1;
+            } else {
+                maze.carve((row, col), (row, col + 1));
+            }
+        }
+    }
+}
+
+// keeps a list of squares that still have unvisited neighbours and grows from one of them.
+// Always picking the newest square behaves like the recursive backtracker, always picking a
+// random one behaves like prim's, newest_bias is the chance of picking the newest
+pub struct GrowingTree {
+    pub newest_bias: f64,
+}
+impl Default for GrowingTree {
+    fn default() -> Self {
+        GrowingTree { newest_bias: 0.5 }
+    }
+}
+impl MazeGenerator for GrowingTree {
+    fn name(&self) -> &'static str {
+        "growing-tree"
+    }
+
+    fn generate(&self, height: usize, width: usize, rng: &mut dyn RngCore) -> Maze {
+        built(height, width, |maze| {
+            growing_tree(maze, height, width, rng, self.newest_bias)
+        })
+    }
+
+    fn steps(&self, height: usize, width: usize, mut rng: MazeRng) -> GenerationSteps {
+        recorded(height, width, |maze| {
+            growing_tree(maze, height, width, &mut rng, self.newest_bias)
+        })
+    }
+}
+
+fn growing_tree(
+    maze: &mut dyn MazeBuilder,
+    height: usize,
+    width: usize,
+    rng: &mut dyn RngCore,
+    newest_bias: f64,
+) {
+    let mut visited = vec![vec![false; width]; height];
+    // the bias can come straight from a level file, and gen_bool panics on NaN
+    let newest_bias = if newest_bias.is_finite() {
+        newest_bias.clamp(0., 1.)
+    } else {
+        GrowingTree::default().newest_bias
+    };
+
+    let start = random_square(height, width, rng);
+    maze.start(start);
+    visited[start.0][start.1] = true;
+    let mut active = vec![start];
+
+    while !active.is_empty() {
+        let index = if rng.gen_bool(newest_bias) {
+            active.len() - 1
+        } else {
+            gen_index(rng, active.len())
+        };
+        let current = active[index];
+
+        let unvisited: Vec<(usize, usize)> = neighbours(height, width, current)
+            .into_iter()
+            .filter(|n| !visited[n.0][n.1])
+            .collect();
+
+        if unvisited.is_empty() {
+            active.remove(index);
+            continue;
+        }
+
+        let next = unvisited[gen_index(rng, unvisited.len())];
+        maze.carve(current, next);
+        visited[next.0][next.1] = true;
+        active.push(next);
+    }
+}
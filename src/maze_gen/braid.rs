@@ -0,0 +1,89 @@
+use rand::Rng;
+
+use super::algorithms::shuffle;
+use super::{gen_index, CellPos, Maze};
+
+// the generators only ever make perfect mazes, with exactly one path between any two squares.
+// These passes add loops afterwards, the f64 is the share (0 to 1) of candidates that get removed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Loops {
+    // open up this share of the dead ends into one of their neighbours
+    Braid(f64),
+    // knock out this share of the walls that are still standing, wherever they are
+    RemoveWalls(f64),
+}
+
+impl Maze {
+    pub fn add_loops<R: Rng + ?Sized>(&mut self, loops: Loops, rng: &mut R) {
+        match loops {
+            Loops::Braid(share) => self.braid(share, rng),
+            Loops::RemoveWalls(share) => self.remove_walls(share, rng),
+        }
+    }
+
+    // squares with only one way out
+    pub fn dead_ends(&self) -> Vec<(usize, usize)> {
+        let mut dead_ends = Vec::new();
+        for row in 0..self.height() {
+            for col in 0..self.width() {
+                if self.open_neighbours((row, col)).len() == 1 {
+                    dead_ends.push((row, col));
+                }
+            }
+        }
+        dead_ends
+    }
+
+    pub fn braid<R: Rng + ?Sized>(&mut self, share: f64, rng: &mut R) {
+        let mut dead_ends = self.dead_ends();
+        shuffle(&mut dead_ends, rng);
+        let count = share_of(dead_ends.len(), share);
+
+        for dead_end in dead_ends.into_iter().take(count) {
+            // an earlier square may have been opened into this one already
+            let open = self.open_neighbours(dead_end);
+            if open.len() != 1 {
+                continue;
+            }
+
+            let closed: Vec<(usize, usize)> = self
+                .neighbours(CellPos::from(dead_end))
+                .into_iter()
+                .map(|(_, n)| n.into())
+                .filter(|n| !open.contains(n))
+                .collect();
+            if closed.is_empty() {
+                continue;
+            }
+
+            // joining two dead ends together gets rid of both of them with a single wall
+            let other_dead_ends: Vec<(usize, usize)> = closed
+                .iter()
+                .copied()
+                .filter(|&n| self.open_neighbours(n).len() == 1)
+                .collect();
+            let candidates = if other_dead_ends.is_empty() {
+                closed
+            } else {
+                other_dead_ends
+            };
+
+            let target = candidates[gen_index(rng, candidates.len())];
+            self.carve(dead_end, target);
+        }
+    }
+
+    pub fn remove_walls<R: Rng + ?Sized>(&mut self, share: f64, rng: &mut R) {
+        let mut standing: Vec<_> = self.inner_walls().collect();
+        shuffle(&mut standing, rng);
+        let count = share_of(standing.len(), share);
+
+        for (cell, direction) in standing.into_iter().take(count) {
+            self.set_wall(cell, direction, false);
+        }
+    }
+}
+
+fn share_of(total: usize, share: f64) -> usize {
+    (total as f64 * share.clamp(0., 1.)).round() as usize
+}
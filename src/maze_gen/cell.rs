@@ -0,0 +1,97 @@
+use super::Maze;
+
+// a square of the maze. Rows count down from the top and columns count right from the left, so
+// (0, 0) is the top left corner. This is the same order the walls are indexed in, and everything
+// that takes squares as (row, column) tuples can be given a CellPos through the conversions below
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct CellPos {
+    pub row: usize,
+    pub col: usize,
+}
+impl CellPos {
+    pub const fn new(row: usize, col: usize) -> Self {
+        CellPos { row, col }
+    }
+
+    // the square next to this one in the given direction. Only the top and left edges are
+    // known here, Maze::neighbour also checks the bottom and right ones
+    pub fn step(self, direction: Direction) -> Option<CellPos> {
+        let CellPos { row, col } = self;
+        Some(match direction {
+            Direction::Up => CellPos::new(row.checked_sub(1)?, col),
+            Direction::Right => CellPos::new(row, col + 1),
+            Direction::Down => CellPos::new(row + 1, col),
+            Direction::Left => CellPos::new(row, col.checked_sub(1)?),
+        })
+    }
+}
+impl From<(usize, usize)> for CellPos {
+    fn from((row, col): (usize, usize)) -> Self {
+        CellPos { row, col }
+    }
+}
+impl From<CellPos> for (usize, usize) {
+    fn from(cell: CellPos) -> Self {
+        (cell.row, cell.col)
+    }
+}
+
+// up is towards row 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+impl Direction {
+    // the generators go through neighbours in this order, changing it changes every seeded maze
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Right => Direction::Left,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+        }
+    }
+
+    // the direction to step in to get from one square to the other, if they are next to each other
+    pub fn between(from: CellPos, to: CellPos) -> Option<Direction> {
+        Direction::ALL
+            .into_iter()
+            .find(|&direction| from.step(direction) == Some(to))
+    }
+}
+
+impl Maze {
+    pub fn contains(&self, cell: CellPos) -> bool {
+        cell.row < self.height() && cell.col < self.width()
+    }
+
+    // the square next to the given one, whether or not there's a wall in between.
+    // None at the edges of the maze
+    pub fn neighbour(&self, cell: CellPos, direction: Direction) -> Option<CellPos> {
+        cell.step(direction).filter(|&next| self.contains(next))
+    }
+
+    // every square next to the given one, in the order of Direction::ALL
+    pub fn neighbours(&self, cell: CellPos) -> Vec<(Direction, CellPos)> {
+        Direction::ALL
+            .into_iter()
+            .filter_map(|direction| Some((direction, self.neighbour(cell, direction)?)))
+            .collect()
+    }
+
+    // whether there's an open passage from the square in the given direction. The outer edges
+    // are always closed
+    pub fn can_move(&self, cell: CellPos, direction: Direction) -> bool {
+        !self.has_wall(cell, direction)
+    }
+}
@@ -0,0 +1,266 @@
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+use super::{Direction, Maze};
+
+pub type Rgb = [u8; 3];
+
+#[derive(Debug, Clone)]
+pub struct ImageStyle {
+    // in pixels, the size of a square includes one wall
+    pub cell_size: u32,
+    pub wall_thickness: u32,
+    pub wall_color: Rgb,
+    pub background_color: Rgb,
+    pub path_color: Rgb,
+    pub start_color: Rgb,
+    pub goal_color: Rgb,
+    // the heatmap fades from the first colour at distance 0 to the second at the furthest square
+    pub heatmap_colors: (Rgb, Rgb),
+}
+impl Default for ImageStyle {
+    fn default() -> Self {
+        ImageStyle {
+            cell_size: 24,
+            wall_thickness: 4,
+            wall_color: [20, 32, 20],
+            background_color: [240, 236, 220],
+            path_color: [214, 69, 65],
+            start_color: [60, 140, 220],
+            goal_color: [80, 180, 80],
+            heatmap_colors: ([255, 244, 180], [60, 90, 170]),
+        }
+    }
+}
+
+// extra information drawn on top of the maze. Squares are (row, column) like everywhere else
+#[derive(Debug, Clone, Default)]
+pub struct ImageOverlays {
+    pub solution: Option<Vec<(usize, usize)>>,
+    // a distance map, as returned by Maze::distances
+    pub heatmap: Option<Vec<Vec<Option<usize>>>>,
+    pub start: Option<(usize, usize)>,
+    pub goal: Option<(usize, usize)>,
+}
+
+// both the svg and the png are drawn from the same list of rectangles, painted in order,
+// so the two always look the same
+struct Rect {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    color: Rgb,
+}
+
+impl Maze {
+    pub fn to_svg(&self, style: &ImageStyle, overlays: &ImageOverlays) -> String {
+        let (width, height) = self.image_size(style);
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\" shape-rendering=\"crispEdges\">\n"
+        );
+
+        for rect in self.shapes(style, overlays) {
+            // writing to a String can't fail
+            let _ = writeln!(
+                svg,
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                rect.x,
+                rect.y,
+                rect.width,
+                rect.height,
+                hex(rect.color)
+            );
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    pub fn save_svg(
+        &self,
+        path: impl AsRef<Path>,
+        style: &ImageStyle,
+        overlays: &ImageOverlays,
+    ) -> io::Result<()> {
+        std::fs::write(path, self.to_svg(style, overlays))
+    }
+
+    // rgb pixels, row by row
+    pub fn to_pixels(&self, style: &ImageStyle, overlays: &ImageOverlays) -> Vec<u8> {
+        let (width, height) = self.image_size(style);
+        let mut pixels = vec![0; width as usize * height as usize * 3];
+
+        for rect in self.shapes(style, overlays) {
+            let x_end = (rect.x + rect.width).min(width);
+            let y_end = (rect.y + rect.height).min(height);
+            for y in rect.y..y_end {
+                for x in rect.x..x_end {
+                    let i = (y as usize * width as usize + x as usize) * 3;
+                    pixels[i..i + 3].copy_from_slice(&rect.color);
+                }
+            }
+        }
+
+        pixels
+    }
+
+    pub fn write_png<W: io::Write>(
+        &self,
+        writer: W,
+        style: &ImageStyle,
+        overlays: &ImageOverlays,
+    ) -> Result<(), png::EncodingError> {
+        let (width, height) = self.image_size(style);
+        let mut encoder = png::Encoder::new(writer, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.to_pixels(style, overlays))?;
+        writer.finish()
+    }
+
+    pub fn save_png(
+        &self,
+        path: impl AsRef<Path>,
+        style: &ImageStyle,
+        overlays: &ImageOverlays,
+    ) -> Result<(), png::EncodingError> {
+        let file = BufWriter::new(File::create(path)?);
+        self.write_png(file, style, overlays)
+    }
+
+    fn image_size(&self, style: &ImageStyle) -> (u32, u32) {
+        (
+            self.width() as u32 * style.cell_size + style.wall_thickness,
+            self.height() as u32 * style.cell_size + style.wall_thickness,
+        )
+    }
+
+    fn shapes(&self, style: &ImageStyle, overlays: &ImageOverlays) -> Vec<Rect> {
+        let (image_width, image_height) = self.image_size(style);
+        let cell = style.cell_size;
+        let wall = style.wall_thickness;
+        let rect = |x, y, width, height, color| Rect {
+            x,
+            y,
+            width,
+            height,
+            color,
+        };
+        // the middle of a square, between its walls
+        let centre = |square: (usize, usize)| {
+            (
+                square.1 as u32 * cell + (cell + wall) / 2,
+                square.0 as u32 * cell + (cell + wall) / 2,
+            )
+        };
+
+        let mut shapes = vec![rect(
+            0,
+            0,
+            image_width,
+            image_height,
+            style.background_color,
+        )];
+
+        if let Some(distances) = &overlays.heatmap {
+            let furthest = distances.iter().flatten().flatten().copied().max();
+            for (row, line) in distances.iter().enumerate() {
+                for (col, distance) in line.iter().enumerate() {
+                    if let (Some(distance), Some(furthest)) = (distance, furthest) {
+                        let t = *distance as f32 / furthest.max(1) as f32;
+                        shapes.push(rect(
+                            col as u32 * cell,
+                            row as u32 * cell,
+                            cell + wall,
+                            cell + wall,
+                            blend(style.heatmap_colors.0, style.heatmap_colors.1, t),
+                        ));
+                    }
+                }
+            }
+        }
+
+        if let Some(solution) = &overlays.solution {
+            let thickness = (cell / 5).max(1);
+            for step in solution.windows(2) {
+                let (a, b) = (centre(step[0]), centre(step[1]));
+                let (left, top) = (a.0.min(b.0), a.1.min(b.1));
+                shapes.push(rect(
+                    left - thickness / 2,
+                    top - thickness / 2,
+                    a.0.abs_diff(b.0) + thickness,
+                    a.1.abs_diff(b.1) + thickness,
+                    style.path_color,
+                ));
+            }
+        }
+
+        let marker = cell / 2;
+        for (square, color) in [
+            (overlays.start, style.start_color),
+            (overlays.goal, style.goal_color),
+        ] {
+            if let Some(square) = square {
+                let (x, y) = centre(square);
+                shapes.push(rect(x - marker / 2, y - marker / 2, marker, marker, color));
+            }
+        }
+
+        // walls go on top of everything else
+        for (square, direction) in self.inner_walls() {
+            let (row, col) = (square.row as u32, square.col as u32);
+            shapes.push(match direction {
+                Direction::Right => rect(
+                    (col + 1) * cell,
+                    row * cell,
+                    wall,
+                    cell + wall,
+                    style.wall_color,
+                ),
+                _ => rect(
+                    col * cell,
+                    (row + 1) * cell,
+                    cell + wall,
+                    wall,
+                    style.wall_color,
+                ),
+            });
+        }
+        shapes.push(rect(0, 0, image_width, wall, style.wall_color));
+        shapes.push(rect(0, 0, wall, image_height, style.wall_color));
+        shapes.push(rect(
+            0,
+            image_height - wall,
+            image_width,
+            wall,
+            style.wall_color,
+        ));
+        shapes.push(rect(
+            image_width - wall,
+            0,
+            wall,
+            image_height,
+            style.wall_color,
+        ));
+
+        shapes
+    }
+}
+
+fn blend(from: Rgb, to: Rgb, t: f32) -> Rgb {
+    let mut color = [0; 3];
+    for i in 0..3 {
+        color[i] = (from[i] as f32 + (to[i] as f32 - from[i] as f32) * t).round() as u8;
+    }
+    color
+}
+
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
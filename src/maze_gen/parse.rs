@@ -0,0 +1,176 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::{CellPos, Direction, Maze};
+
+// reads back the drawing that display_maze prints, e.g. for a 2x3 maze:
+//
+//  ________
+// |__   |  |
+// |________|
+//
+// the top line is a space followed by underscores and decides the width. Every line after it is
+// one row of squares: two characters per square ("__" for a wall below it, "  " for none) with a
+// single character between squares ("|" for a wall, " " or "_" for none)
+
+#[derive(Debug)]
+pub enum ParseMazeError {
+    Io(io::Error),
+    Empty,
+    // lines and columns start at 1, like in a text editor
+    WrongLength {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    UnexpectedGlyph {
+        line: usize,
+        column: usize,
+        found: char,
+        expected: &'static str,
+    },
+}
+impl fmt::Display for ParseMazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseMazeError::Io(e) => write!(f, "could not read maze drawing: {e}"),
+            ParseMazeError::Empty => {
+                write!(f, "maze drawing needs a top wall and at least one row")
+            }
+            ParseMazeError::WrongLength {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line} should be {expected} characters long, but is {found}"
+            ),
+            ParseMazeError::UnexpectedGlyph {
+                line,
+                column,
+                found,
+                expected,
+            } => write!(
+                f,
+                "unexpected {found:?} at line {line}, column {column}, expected {expected}"
+            ),
+        }
+    }
+}
+impl std::error::Error for ParseMazeError {}
+
+impl From<io::Error> for ParseMazeError {
+    fn from(e: io::Error) -> Self {
+        ParseMazeError::Io(e)
+    }
+}
+
+impl FromStr for Maze {
+    type Err = ParseMazeError;
+
+    fn from_str(drawing: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<Vec<char>> = drawing
+            .trim_end()
+            .lines()
+            .map(|l| l.trim_end().chars().collect())
+            .collect();
+        if lines.len() < 2 {
+            return Err(ParseMazeError::Empty);
+        }
+
+        let width = parse_top_wall(&lines[0])?;
+        let height = lines.len() - 1;
+
+        let mut maze = Maze::closed(height, width);
+
+        for (row, chars) in lines[1..].iter().enumerate() {
+            let line = row + 2;
+            let last_row = row == height - 1;
+
+            let expected = 3 * width + 1;
+            if chars.len() != expected {
+                return Err(ParseMazeError::WrongLength {
+                    line,
+                    expected,
+                    found: chars.len(),
+                });
+            }
+
+            expect_glyph(chars, line, 0, &['|'], "'|' for the left wall")?;
+            expect_glyph(chars, line, 3 * width, &['|'], "'|' for the right wall")?;
+
+            for col in 0..width {
+                let floor = 1 + 3 * col;
+                let wall_below = if last_row {
+                    expect_glyph(chars, line, floor, &['_'], "'_' for the bottom wall")?;
+                    expect_glyph(chars, line, floor + 1, &['_'], "'_' for the bottom wall")?;
+                    true
+                } else {
+                    let first = expect_glyph(chars, line, floor, &['_', ' '], "'_' or ' '")?;
+                    let expected = if first == '_' { "'_'" } else { "' '" };
+                    expect_glyph(chars, line, floor + 1, &[first], expected)?;
+                    first == '_'
+                };
+                if !last_row {
+                    maze.set_wall(CellPos::new(row, col), Direction::Down, wall_below);
+                }
+
+                if col < width - 1 {
+                    let glyph =
+                        expect_glyph(chars, line, floor + 2, &['|', '_', ' '], "'|', '_' or ' '")?;
+                    maze.set_wall(CellPos::new(row, col), Direction::Right, glyph == '|');
+                }
+            }
+        }
+
+        Ok(maze)
+    }
+}
+
+impl Maze {
+    pub fn load_ascii(path: impl AsRef<Path>) -> Result<Self, ParseMazeError> {
+        fs::read_to_string(path)?.parse()
+    }
+}
+
+// the top wall is a space for the entrance followed by underscores, 3 characters per square
+fn parse_top_wall(chars: &[char]) -> Result<usize, ParseMazeError> {
+    if chars.len() < 3 || !chars.len().is_multiple_of(3) {
+        return Err(ParseMazeError::WrongLength {
+            line: 1,
+            expected: 3 * (chars.len() / 3).max(1),
+            found: chars.len(),
+        });
+    }
+
+    expect_glyph(chars, 1, 0, &[' '], "' ' for the top left corner")?;
+    for column in 1..chars.len() {
+        expect_glyph(chars, 1, column, &['_'], "'_' for the top wall")?;
+    }
+
+    Ok(chars.len() / 3)
+}
+
+// check the character at the (0 based) index is one of the allowed ones and return it
+fn expect_glyph(
+    chars: &[char],
+    line: usize,
+    index: usize,
+    allowed: &[char],
+    expected: &'static str,
+) -> Result<char, ParseMazeError> {
+    let found = chars[index];
+    if allowed.contains(&found) {
+        Ok(found)
+    } else {
+        Err(ParseMazeError::UnexpectedGlyph {
+            line,
+            column: index + 1,
+            found,
+            expected,
+        })
+    }
+}
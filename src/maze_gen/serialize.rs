@@ -0,0 +1,228 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::walls::wall_count;
+use super::{Maze, NestedWalls};
+
+// bump this whenever the layout of MazeFile or the binary encoding changes
+pub const FORMAT_VERSION: u32 = 1;
+
+// the binary encoding is the magic bytes, then version, height and width as little endian u32s,
+// then every wall as a single bit. walls.0 comes first and then walls.1, both row by row, with
+// the lowest bit of each byte first
+const MAGIC: &[u8; 4] = b"MAZE";
+const HEADER_LEN: usize = MAGIC.len() + 3 * 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MazeFormat {
+    Json,
+    Ron,
+    Binary,
+}
+impl MazeFormat {
+    // pick the format from the file extension, anything that isn't json or ron is binary
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => MazeFormat::Json,
+            Some("ron") => MazeFormat::Ron,
+            _ => MazeFormat::Binary,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MazeFormatError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    // reading ron knows where in the input it went wrong
+    Ron(ron::error::SpannedError),
+    // writing ron doesn't, so there's no position to report
+    RonWrite(ron::Error),
+    UnsupportedVersion(u32),
+    // the wall vectors don't match the dimensions the file claims to have
+    InvalidDimensions(String),
+    NotAMaze,
+    // the binary data ends before all of the walls have been read, or carries extra bytes
+    WrongLength { expected: usize, found: usize },
+}
+impl fmt::Display for MazeFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MazeFormatError::Io(e) => write!(f, "could not read or write maze file: {e}"),
+            MazeFormatError::Json(e) => write!(f, "invalid maze json: {e}"),
+            MazeFormatError::Ron(e) => write!(f, "invalid maze ron: {e}"),
+            MazeFormatError::RonWrite(e) => write!(f, "could not write maze ron: {e}"),
+            MazeFormatError::UnsupportedVersion(version) => write!(
+                f,
+                "maze format version {version} is not supported, expected {FORMAT_VERSION}"
+            ),
+            MazeFormatError::InvalidDimensions(reason) => {
+                write!(f, "maze dimensions are invalid: {reason}")
+            }
+            MazeFormatError::NotAMaze => write!(f, "data does not start with the maze header"),
+            MazeFormatError::WrongLength { expected, found } => write!(
+                f,
+                "binary maze should be {expected} bytes long, but is {found} bytes"
+            ),
+        }
+    }
+}
+impl std::error::Error for MazeFormatError {}
+
+impl From<io::Error> for MazeFormatError {
+    fn from(e: io::Error) -> Self {
+        MazeFormatError::Io(e)
+    }
+}
+impl From<serde_json::Error> for MazeFormatError {
+    fn from(e: serde_json::Error) -> Self {
+        MazeFormatError::Json(e)
+    }
+}
+impl From<ron::error::SpannedError> for MazeFormatError {
+    fn from(e: ron::error::SpannedError) -> Self {
+        MazeFormatError::Ron(e)
+    }
+}
+impl From<ron::Error> for MazeFormatError {
+    fn from(e: ron::Error) -> Self {
+        MazeFormatError::RonWrite(e)
+    }
+}
+
+// what a Maze looks like on disk
+#[derive(Serialize, Deserialize)]
+pub(super) struct MazeFile {
+    version: u32,
+    height: usize,
+    width: usize,
+    walls: NestedWalls,
+}
+
+impl From<Maze> for MazeFile {
+    fn from(maze: Maze) -> Self {
+        MazeFile {
+            version: FORMAT_VERSION,
+            height: maze.height(),
+            width: maze.width(),
+            walls: maze.to_nested_walls(),
+        }
+    }
+}
+
+impl TryFrom<MazeFile> for Maze {
+    type Error = MazeFormatError;
+
+    fn try_from(file: MazeFile) -> Result<Self, Self::Error> {
+        if file.version != FORMAT_VERSION {
+            return Err(MazeFormatError::UnsupportedVersion(file.version));
+        }
+        Maze::from_nested_walls(file.height, file.width, &file.walls)
+    }
+}
+
+pub(super) fn validate_dimensions(height: usize, width: usize) -> Result<(), MazeFormatError> {
+    if height == 0 || width == 0 {
+        return Err(MazeFormatError::InvalidDimensions(format!(
+            "a maze can't be {height}x{width}"
+        )));
+    }
+    Ok(())
+}
+
+impl Maze {
+    pub fn to_json(&self) -> Result<String, MazeFormatError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, MazeFormatError> {
+        let file: MazeFile = serde_json::from_str(json)?;
+        Maze::try_from(file)
+    }
+
+    pub fn to_ron(&self) -> Result<String, MazeFormatError> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default(),
+        )?)
+    }
+
+    pub fn from_ron(ron: &str) -> Result<Self, MazeFormatError> {
+        let file: MazeFile = ron::from_str(ron)?;
+        Maze::try_from(file)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (height, width) = (self.height(), self.width());
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + wall_bytes(height, width));
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(height as u32).to_le_bytes());
+        bytes.extend_from_slice(&(width as u32).to_le_bytes());
+
+        bytes.extend(self.packed_walls());
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MazeFormatError> {
+        if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(MazeFormatError::NotAMaze);
+        }
+        let read_u32 = |at: usize| {
+            let mut word = [0; 4];
+            word.copy_from_slice(&bytes[at..at + 4]);
+            u32::from_le_bytes(word)
+        };
+
+        let version = read_u32(4);
+        if version != FORMAT_VERSION {
+            return Err(MazeFormatError::UnsupportedVersion(version));
+        }
+        let height = read_u32(8) as usize;
+        let width = read_u32(12) as usize;
+        validate_dimensions(height, width)?;
+
+        // checking the length before allocating anything means a corrupted header can't make us
+        // try to allocate a maze billions of squares big
+        let expected = HEADER_LEN.saturating_add(wall_bytes(height, width));
+        if bytes.len() != expected {
+            return Err(MazeFormatError::WrongLength {
+                expected,
+                found: bytes.len(),
+            });
+        }
+
+        Ok(Maze::from_packed_walls(height, width, &bytes[HEADER_LEN..]))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), MazeFormatError> {
+        let path = path.as_ref();
+        match MazeFormat::from_path(path) {
+            MazeFormat::Json => fs::write(path, self.to_json()?)?,
+            MazeFormat::Ron => fs::write(path, self.to_ron()?)?,
+            MazeFormat::Binary => fs::write(path, self.to_bytes())?,
+        }
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, MazeFormatError> {
+        let path = path.as_ref();
+        match MazeFormat::from_path(path) {
+            MazeFormat::Json => Maze::from_json(&fs::read_to_string(path)?),
+            MazeFormat::Ron => Maze::from_ron(&fs::read_to_string(path)?),
+            MazeFormat::Binary => Maze::from_bytes(&fs::read(path)?),
+        }
+    }
+}
+
+// number of bytes needed to hold every wall of a maze, one bit each. For nonsense dimensions
+// this saturates, so the length check just fails
+fn wall_bytes(height: usize, width: usize) -> usize {
+    wall_count(height, width).div_ceil(8)
+}
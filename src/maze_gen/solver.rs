@@ -0,0 +1,147 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+use super::{CellPos, Maze};
+
+// all squares are given as (row, column) with (0, 0) in the top left corner, the same way the
+// generators index the walls
+impl Maze {
+    // the squares that can be reached from the given one in a single step
+    pub fn open_neighbours(&self, square: (usize, usize)) -> Vec<(usize, usize)> {
+        let cell = CellPos::from(square);
+        self.neighbours(cell)
+            .into_iter()
+            .filter(|&(direction, _)| self.can_move(cell, direction))
+            .map(|(_, next)| next.into())
+            .collect()
+    }
+
+    // number of steps from the given square to every other square,
+    // None for squares that can't be reached. Nothing can be reached from outside of the maze
+    pub fn distances(&self, from: (usize, usize)) -> Vec<Vec<Option<usize>>> {
+        let mut distances = vec![vec![None; self.width()]; self.height()];
+        if !self.contains(from.into()) {
+            return distances;
+        }
+        distances[from.0][from.1] = Some(0);
+
+        let mut queue = VecDeque::from([from]);
+        while let Some(square) = queue.pop_front() {
+            let distance = distances[square.0][square.1].unwrap_or_default();
+            for n in self.open_neighbours(square) {
+                if distances[n.0][n.1].is_none() {
+                    distances[n.0][n.1] = Some(distance + 1);
+                    queue.push_back(n);
+                }
+            }
+        }
+
+        distances
+    }
+
+    // the square that takes the most steps to reach from the given one, and how many steps that
+    // is. Ties go to the first square found row by row, and a square outside of the maze is its
+    // own farthest square
+    pub fn farthest_from(&self, from: (usize, usize)) -> ((usize, usize), usize) {
+        let mut farthest = (from, 0);
+        for (row, line) in self.distances(from).iter().enumerate() {
+            for (col, distance) in line.iter().enumerate() {
+                if let Some(distance) = *distance {
+                    if distance > farthest.1 {
+                        farthest = ((row, col), distance);
+                    }
+                }
+            }
+        }
+        farthest
+    }
+
+    pub fn is_connected(&self, a: (usize, usize), b: (usize, usize)) -> bool {
+        self.shortest_path(a, b).is_some()
+    }
+
+    // breadth first search, the path includes both the start and the end square. There's no path
+    // to or from a square outside of the maze
+    pub fn shortest_path(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> Option<Vec<(usize, usize)>> {
+        if !self.contains(from.into()) || !self.contains(to.into()) {
+            return None;
+        }
+        let mut came_from: Vec<Vec<Option<(usize, usize)>>> =
+            vec![vec![None; self.width()]; self.height()];
+        came_from[from.0][from.1] = Some(from);
+
+        let mut queue = VecDeque::from([from]);
+        while let Some(square) = queue.pop_front() {
+            if square == to {
+                return Some(trace_path(&came_from, from, to));
+            }
+            for n in self.open_neighbours(square) {
+                if came_from[n.0][n.1].is_none() {
+                    came_from[n.0][n.1] = Some(square);
+                    queue.push_back(n);
+                }
+            }
+        }
+
+        None
+    }
+
+    // A* with the manhattan distance as heuristic. Finds a path of the same length as
+    // shortest_path, but usually visits far fewer squares on big mazes with loops in them
+    pub fn a_star(&self, from: (usize, usize), to: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        if !self.contains(from.into()) || !self.contains(to.into()) {
+            return None;
+        }
+        let heuristic = |square: (usize, usize)| square.0.abs_diff(to.0) + square.1.abs_diff(to.1);
+
+        let mut came_from: Vec<Vec<Option<(usize, usize)>>> =
+            vec![vec![None; self.width()]; self.height()];
+        let mut cost = vec![vec![usize::MAX; self.width()]; self.height()];
+        came_from[from.0][from.1] = Some(from);
+        cost[from.0][from.1] = 0;
+
+        // ordered by estimated total cost, then by cost so far to break ties deterministically
+        let mut open = BinaryHeap::from([Reverse((heuristic(from), 0, from))]);
+        while let Some(Reverse((_, square_cost, square))) = open.pop() {
+            if square == to {
+                return Some(trace_path(&came_from, from, to));
+            }
+            // stale entry, the square has been reached more cheaply since it was pushed
+            if square_cost > cost[square.0][square.1] {
+                continue;
+            }
+
+            for n in self.open_neighbours(square) {
+                let n_cost = square_cost + 1;
+                if n_cost < cost[n.0][n.1] {
+                    cost[n.0][n.1] = n_cost;
+                    came_from[n.0][n.1] = Some(square);
+                    open.push(Reverse((n_cost + heuristic(n), n_cost, n)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+fn trace_path(
+    came_from: &[Vec<Option<(usize, usize)>>],
+    from: (usize, usize),
+    to: (usize, usize),
+) -> Vec<(usize, usize)> {
+    let mut path = vec![to];
+    let mut current = to;
+    while current != from {
+        // every square on the way back was reached from somewhere, or the search wouldn't
+        // have got to `to`
+        current = came_from[current.0][current.1].unwrap();
+        path.push(current);
+    }
+    path.reverse();
+    path
+}
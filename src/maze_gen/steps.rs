@@ -0,0 +1,282 @@
+use std::collections::VecDeque;
+
+use rand::{Rng, SeedableRng};
+
+use super::{CellPos, IndexedSet, Maze, MazeGenerator, MazeRng, RandomDirections, WalkPosition};
+
+// what happened at one step of building a maze. Starting from Maze::closed and applying every
+// PathCarved gives the same maze the generator's generate does from the same rng
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenerationEvent {
+    // the random walk moved onto a square. A new walk starts from None
+    WalkStep { from: Option<CellPos>, to: CellPos },
+    // the walk ran into itself at the square, and the loop it made is taken off again. erased is
+    // every square that was on the walk after it, in the order they were walked
+    LoopErased { at: CellPos, erased: Vec<CellPos> },
+    // the wall between the two squares was knocked down. When it joins a new square onto the
+    // maze, that's from and a CellJoined for it comes straight after. Kruskal's, eller's, the
+    // binary tree and the sidewinder build several pieces at once, so both squares can be new, and
+    // both get a CellJoined, or both already be part of a piece
+    PathCarved { from: CellPos, to: CellPos },
+    // the square became part of the maze
+    CellJoined(CellPos),
+}
+
+// owns its rng so that it can be kept around and stepped through a bit at a time, e.g. a few
+// events every frame
+pub type GenerationSteps = Box<dyn Iterator<Item = GenerationEvent> + Send + Sync>;
+
+impl Maze {
+    // the steps the generator takes to build a maze, see GenerationEvent
+    pub fn steps_with(
+        generator: &dyn MazeGenerator,
+        height: usize,
+        width: usize,
+        seed: u64,
+    ) -> GenerationSteps {
+        generator.steps(height, width, MazeRng::seed_from_u64(seed))
+    }
+
+    pub fn apply(&mut self, event: &GenerationEvent) {
+        if let GenerationEvent::PathCarved { from, to } = *event {
+            self.carve(from.into(), to.into());
+        }
+    }
+}
+
+// what the generators in the algorithms module build with. They're written once against this, so
+// that the maze and the steps leading up to it come from the same code and the same rng draws
+pub(super) trait MazeBuilder {
+    // the square is part of the maze without a wall coming down for it, like the square a
+    // generator grows the maze out from
+    fn start(&mut self, square: (usize, usize));
+    // remove the wall between two neighbouring squares
+    fn carve(&mut self, a: (usize, usize), b: (usize, usize));
+}
+
+impl MazeBuilder for Maze {
+    fn start(&mut self, _: (usize, usize)) {}
+
+    fn carve(&mut self, a: (usize, usize), b: (usize, usize)) {
+        Maze::carve(self, a, b);
+    }
+}
+
+// writes down the events instead of building the maze
+struct Recorder {
+    width: usize,
+    joined: Vec<bool>,
+    events: Vec<GenerationEvent>,
+}
+impl Recorder {
+    fn join(&mut self, square: CellPos) {
+        let index = square.row * self.width + square.col;
+        if !self.joined[index] {
+            self.joined[index] = true;
+            self.events.push(GenerationEvent::CellJoined(square));
+        }
+    }
+}
+impl MazeBuilder for Recorder {
+    fn start(&mut self, square: (usize, usize)) {
+        self.join(square.into());
+    }
+
+    fn carve(&mut self, a: (usize, usize), b: (usize, usize)) {
+        let (mut from, mut to) = (CellPos::from(a), CellPos::from(b));
+        // carved from the new square onto the maze, when only one of them is part of it
+        if self.joined[from.row * self.width + from.col] {
+            std::mem::swap(&mut from, &mut to);
+        }
+        self.events.push(GenerationEvent::PathCarved { from, to });
+        self.join(from);
+        self.join(to);
+    }
+}
+
+pub(super) fn built(height: usize, width: usize, build: impl FnOnce(&mut dyn MazeBuilder)) -> Maze {
+    let mut maze = Maze::closed(height, width);
+    build(&mut maze);
+    maze
+}
+
+pub(super) fn recorded(
+    height: usize,
+    width: usize,
+    build: impl FnOnce(&mut dyn MazeBuilder),
+) -> GenerationSteps {
+    // the same checks Maze::closed does, since no maze is made here
+    assert!(height > 0 && width > 0, "a maze can't be {height}x{width}");
+    let mut recorder = Recorder {
+        width,
+        joined: vec![false; height * width],
+        events: Vec::new(),
+    };
+    build(&mut recorder);
+    // a maze of one square has no walls to take down, so nothing else joins it
+    recorder.join(CellPos::new(0, 0));
+    Box::new(recorder.events.into_iter())
+}
+
+// for generators that don't know their own steps: the finished maze, joined square by square
+// going outwards from the top left corner. The walls come down in a different order than they
+// did while generating, but it's the same maze
+pub(super) fn replay(maze: Maze) -> GenerationSteps {
+    let start = CellPos::new(0, 0);
+    let mut joined = vec![vec![false; maze.width()]; maze.height()];
+    joined[0][0] = true;
+
+    let mut events = vec![GenerationEvent::CellJoined(start)];
+    let mut queue = VecDeque::from([start]);
+    while let Some(cell) = queue.pop_front() {
+        for direction in maze.exits(cell) {
+            let Some(next) = maze.neighbour(cell, direction) else {
+                continue;
+            };
+            if joined[next.row][next.col] {
+                continue;
+            }
+            joined[next.row][next.col] = true;
+            events.push(GenerationEvent::PathCarved {
+                from: next,
+                to: cell,
+            });
+            events.push(GenerationEvent::CellJoined(next));
+            queue.push_back(next);
+        }
+    }
+
+    Box::new(events.into_iter())
+}
+
+// the visited map of WilsonsSteps has IN_MAZE for squares already carved, otherwise where the
+// square is on the current walk plus one, or 0 if it isn't on it. The whole walk is kept here
+// since the loop erased events need the squares they erase
+const IN_MAZE: u32 = u32::MAX;
+
+// wilson's algorithm one walk step at a time. It draws from the rng in exactly the same order as
+// wilsons_algorithm, so both give the same maze
+pub(super) struct WilsonsSteps<R> {
+    rng: R,
+    height: usize,
+    width: usize,
+    outside: IndexedSet,
+    visited: Vec<u32>,
+    walk: Vec<usize>,
+    position: WalkPosition,
+    directions: RandomDirections,
+    // the events of the last step that haven't been handed out yet
+    pending: VecDeque<GenerationEvent>,
+}
+impl<R: Rng> WilsonsSteps<R> {
+    pub(super) fn new(height: usize, width: usize, mut rng: R) -> Self {
+        // the same checks Maze::closed does, since no maze is made here
+        assert!(height > 0 && width > 0, "a maze can't be {height}x{width}");
+        let mut outside = IndexedSet::full(height * width);
+        let mut visited = vec![0; height * width];
+        let first = outside.pick(&mut rng);
+        outside.remove(first);
+        visited[first] = IN_MAZE;
+
+        let mut steps = WilsonsSteps {
+            rng,
+            height,
+            width,
+            outside,
+            visited,
+            walk: Vec::new(),
+            position: WalkPosition::new(first, width),
+            directions: RandomDirections::default(),
+            pending: VecDeque::new(),
+        };
+        steps
+            .pending
+            .push_back(GenerationEvent::CellJoined(steps.cell(first)));
+        steps
+    }
+
+    fn cell(&self, index: usize) -> CellPos {
+        CellPos::new(index / self.width, index % self.width)
+    }
+
+    // queues the events of the next step, false once the maze is finished
+    fn advance(&mut self) -> bool {
+        if self.walk.is_empty() {
+            if self.outside.is_empty() {
+                return false;
+            }
+            let start = self.outside.pick(&mut self.rng);
+            self.walk.push(start);
+            self.visited[start] = 1;
+            self.position = WalkPosition::new(start, self.width);
+            self.pending.push_back(GenerationEvent::WalkStep {
+                from: None,
+                to: self.cell(start),
+            });
+            return true;
+        }
+
+        let from = self.cell(self.position.index);
+        self.position = self
+            .directions
+            .step(&mut self.rng, self.height, self.width, self.position);
+        let next = self.position.index;
+        self.pending.push_back(GenerationEvent::WalkStep {
+            from: Some(from),
+            to: self.cell(next),
+        });
+
+        match self.visited[next] {
+            IN_MAZE => {
+                self.walk.push(next);
+                self.join_walk();
+            }
+            0 => {
+                self.walk.push(next);
+                self.visited[next] = self.walk.len() as u32;
+            }
+            keep => {
+                let erased: Vec<_> = self.walk.drain(keep as usize..).collect();
+                for &square in &erased {
+                    self.visited[square] = 0;
+                }
+                self.pending.push_back(GenerationEvent::LoopErased {
+                    at: self.cell(next),
+                    erased: erased.into_iter().map(|square| self.cell(square)).collect(),
+                });
+            }
+        }
+        true
+    }
+
+    // the walk has hit the maze, carve it in starting from the end that touches the maze
+    fn join_walk(&mut self) {
+        let walk = std::mem::take(&mut self.walk);
+        // taken out in the same order as wilsons_algorithm does, which decides the later picks
+        for &square in &walk[..walk.len() - 1] {
+            self.visited[square] = IN_MAZE;
+            self.outside.remove(square);
+        }
+        for step in walk.windows(2).rev() {
+            let (from, to) = (self.cell(step[0]), self.cell(step[1]));
+            self.pending
+                .push_back(GenerationEvent::PathCarved { from, to });
+            self.pending.push_back(GenerationEvent::CellJoined(from));
+        }
+        // keeps the allocation for the next walk
+        self.walk = walk;
+        self.walk.clear();
+    }
+}
+impl<R: Rng> Iterator for WilsonsSteps<R> {
+    type Item = GenerationEvent;
+
+    fn next(&mut self) -> Option<GenerationEvent> {
+        while self.pending.is_empty() {
+            if !self.advance() {
+                return None;
+            }
+        }
+        self.pending.pop_front()
+    }
+}
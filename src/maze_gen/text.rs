@@ -0,0 +1,242 @@
+use std::{fmt, io};
+
+use super::{CellPos, Direction, Maze};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextStyle {
+    // the original display_maze drawing, which the parser can read back
+    Underscore,
+    // unicode lines with proper junctions, ┌──┬──┐
+    BoxDrawing,
+    // every square and every wall is a block two characters wide
+    Blocks,
+}
+
+// squares are (row, column). When several overlays cover a square the player wins over the
+// solution, which wins over visited
+#[derive(Debug, Clone, Default)]
+pub struct TextOverlays {
+    pub player: Option<(usize, usize)>,
+    pub solution: Option<Vec<(usize, usize)>>,
+    // one entry per square, like the distance map
+    pub visited: Option<Vec<Vec<bool>>>,
+}
+
+const PLAYER: char = '@';
+const SOLUTION: char = '*';
+const VISITED: char = '.';
+
+// the drawing says far more than the bits do when two mazes don't match
+impl fmt::Debug for Maze {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Maze {}x{}", self.height(), self.width())?;
+        f.write_str(&self.render_text(TextStyle::Underscore, &TextOverlays::default()))
+    }
+}
+
+impl Maze {
+    pub fn render_text(&self, style: TextStyle, overlays: &TextOverlays) -> String {
+        let markers = self.markers(overlays);
+        match style {
+            TextStyle::Underscore => self.render_underscore(&markers),
+            TextStyle::BoxDrawing => self.render_box_drawing(&markers),
+            TextStyle::Blocks => self.render_blocks(&markers),
+        }
+    }
+
+    pub fn write_text<W: io::Write>(
+        &self,
+        mut writer: W,
+        style: TextStyle,
+        overlays: &TextOverlays,
+    ) -> io::Result<()> {
+        writer.write_all(self.render_text(style, overlays).as_bytes())?;
+        writer.flush()
+    }
+
+    fn markers(&self, overlays: &TextOverlays) -> Vec<Vec<Option<char>>> {
+        let mut markers = vec![vec![None; self.width()]; self.height()];
+
+        if let Some(visited) = &overlays.visited {
+            for (row, line) in visited.iter().enumerate().take(self.height()) {
+                for (col, &seen) in line.iter().enumerate().take(self.width()) {
+                    if seen {
+                        markers[row][col] = Some(VISITED);
+                    }
+                }
+            }
+        }
+        // squares outside of the maze are left out, like the extra rows and columns of visited
+        if let Some(solution) = &overlays.solution {
+            for &square in solution {
+                if self.contains(square.into()) {
+                    markers[square.0][square.1] = Some(SOLUTION);
+                }
+            }
+        }
+        if let Some(square) = overlays
+            .player
+            .filter(|&square| self.contains(square.into()))
+        {
+            markers[square.0][square.1] = Some(PLAYER);
+        }
+
+        markers
+    }
+
+    // the bottom and right edges count as walls
+    fn wall_right(&self, row: usize, col: usize) -> bool {
+        self.has_wall(CellPos::new(row, col), Direction::Right)
+    }
+
+    fn wall_below(&self, row: usize, col: usize) -> bool {
+        self.has_wall(CellPos::new(row, col), Direction::Down)
+    }
+
+    fn render_underscore(&self, markers: &[Vec<Option<char>>]) -> String {
+        let width = self.width();
+        let mut text = String::new();
+
+        // top wall
+        // there should be a gap in the top left corner
+        text.push(' ');
+        for _ in 0..width - 1 {
+            text.push_str("___");
+        }
+        // the last one should leave a gap in the corner
+        text.push_str("__\n");
+
+        for (h, row_markers) in markers.iter().enumerate() {
+            text.push('|');
+            for (w, marker) in row_markers.iter().enumerate() {
+                // for the horizontal walls, the lowest layer is always filled in
+                let floor = if self.wall_below(h, w) { '_' } else { ' ' };
+                text.push(marker.unwrap_or(floor));
+                text.push(floor);
+
+                // for the vertical walls
+                if w < width - 1 {
+                    if self.wall_right(h, w) {
+                        text.push('|');
+                    } else if self.wall_below(h, w) && self.wall_below(h, w + 1) {
+                        // if both of the surrounding horizontal lines are filled in,
+                        // it looks a bit strange if there's a gap between them
+                        // this also applies if we're at the lowest level
+                        text.push('_');
+                    } else {
+                        text.push(' ');
+                    }
+                }
+            }
+            text.push_str("|\n");
+        }
+
+        text
+    }
+
+    fn render_box_drawing(&self, markers: &[Vec<Option<char>>]) -> String {
+        let (height, width) = (self.height(), self.width());
+        // walls along the lines between squares, including the outer edges. A vertical line at
+        // column boundary `j` in row `i`, and a horizontal line at row boundary `i` in column `j`
+        let vertical = |i: usize, j: usize| j == 0 || self.wall_right(i, j - 1);
+        let horizontal = |i: usize, j: usize| i == 0 || self.wall_below(i - 1, j);
+
+        let mut text = String::new();
+        for i in 0..=height {
+            // the line of corners and horizontal walls above row i
+            for j in 0..=width {
+                let up = i > 0 && vertical(i - 1, j);
+                let down = i < height && vertical(i, j);
+                let left = j > 0 && horizontal(i, j - 1);
+                let right = j < width && horizontal(i, j);
+                text.push(junction(up, right, down, left));
+
+                if j < width {
+                    text.push_str(if horizontal(i, j) { "───" } else { "   " });
+                }
+            }
+            text.push('\n');
+
+            // the squares themselves, there are none below the last line
+            let Some(row_markers) = markers.get(i) else {
+                break;
+            };
+            for j in 0..=width {
+                text.push(if vertical(i, j) { '│' } else { ' ' });
+                if let Some(marker) = row_markers.get(j) {
+                    text.push(' ');
+                    text.push(marker.unwrap_or(' '));
+                    text.push(' ');
+                }
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+
+    fn render_blocks(&self, markers: &[Vec<Option<char>>]) -> String {
+        const WALL: &str = "██";
+        const OPEN: &str = "  ";
+        let width = self.width();
+
+        let mut text = WALL.repeat(2 * width + 1);
+        text.push('\n');
+
+        for (row, row_markers) in markers.iter().enumerate() {
+            // the squares and the walls between them
+            text.push_str(WALL);
+            for (col, marker) in row_markers.iter().enumerate() {
+                match marker {
+                    Some(marker) => {
+                        text.push(*marker);
+                        text.push(*marker);
+                    }
+                    None => text.push_str(OPEN),
+                }
+                text.push_str(if self.wall_right(row, col) {
+                    WALL
+                } else {
+                    OPEN
+                });
+            }
+            text.push('\n');
+
+            // the walls below the squares, the corners between them are always solid
+            text.push_str(WALL);
+            for col in 0..width {
+                text.push_str(if self.wall_below(row, col) {
+                    WALL
+                } else {
+                    OPEN
+                });
+                text.push_str(WALL);
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+}
+
+// the box drawing character with lines going out in the given directions
+fn junction(up: bool, right: bool, down: bool, left: bool) -> char {
+    match (up, right, down, left) {
+        (false, false, false, false) => ' ',
+        (true, false, false, false) => '╵',
+        (false, true, false, false) => '╶',
+        (false, false, true, false) => '╷',
+        (false, false, false, true) => '╴',
+        (true, false, true, false) => '│',
+        (false, true, false, true) => '─',
+        (true, true, false, false) => '└',
+        (false, true, true, false) => '┌',
+        (false, false, true, true) => '┐',
+        (true, false, false, true) => '┘',
+        (true, true, true, false) => '├',
+        (true, false, true, true) => '┤',
+        (false, true, true, true) => '┬',
+        (true, true, false, true) => '┴',
+        (true, true, true, true) => '┼',
+    }
+}
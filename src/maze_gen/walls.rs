@@ -0,0 +1,188 @@
+use super::serialize::{validate_dimensions, MazeFormatError};
+use super::{CellPos, Direction, Maze};
+
+// the walls as nested vectors, walls.0 are the ones between columns (height rows of width - 1)
+// and walls.1 the ones between rows (height - 1 rows of width). This is how mazes used to be
+// stored and how the json and ron files still spell them out
+pub type NestedWalls = (Vec<Vec<bool>>, Vec<Vec<bool>>);
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+// every inner wall is a single bit, in the same order as NestedWalls: the walls between columns
+// row by row, then the walls between rows row by row. The outer edges are always there and
+// aren't stored at all
+impl Maze {
+    // a maze where every wall is still standing, the generators carve their passages out of this
+    pub fn closed(height: usize, width: usize) -> Self {
+        assert!(height > 0 && width > 0, "a maze can't be {height}x{width}");
+        let count = wall_count(height, width);
+        let mut bits = vec![u64::MAX; count.div_ceil(WORD_BITS)];
+        // the bits past the last wall stay clear, so mazes with the same walls compare equal
+        if !count.is_multiple_of(WORD_BITS) {
+            *bits.last_mut().unwrap() = (1 << (count % WORD_BITS)) - 1;
+        }
+        Maze {
+            height,
+            width,
+            bits,
+        }
+    }
+
+    // whether there's a wall on the given side of the square. The outer edges always count as
+    // walls, and so does every side of a square outside the maze
+    pub fn has_wall(&self, cell: CellPos, direction: Direction) -> bool {
+        match self.wall_index(cell, direction) {
+            Some(index) => self.bits[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0,
+            None => true,
+        }
+    }
+
+    // put up or knock down the wall on the given side of the square. Neighbouring squares share
+    // their walls, so this changes the wall from both sides
+    pub fn set_wall(&mut self, cell: CellPos, direction: Direction, wall: bool) {
+        let Some(index) = self.wall_index(cell, direction) else {
+            panic!("the {direction:?} side of {cell:?} is on the outer edge of the maze");
+        };
+        self.set_bit(index, wall);
+    }
+
+    // every open passage between two squares, once each: from the square above or to the left,
+    // going Down or Right
+    pub fn passages(&self) -> impl Iterator<Item = (CellPos, Direction)> + '_ {
+        self.inner_sides()
+            .filter(|&(cell, direction)| !self.has_wall(cell, direction))
+    }
+
+    // every wall between two squares that's still standing, given the same way as passages
+    pub fn inner_walls(&self) -> impl Iterator<Item = (CellPos, Direction)> + '_ {
+        self.inner_sides()
+            .filter(|&(cell, direction)| self.has_wall(cell, direction))
+    }
+
+    // the directions that can be moved in from the given square, in the order of Direction::ALL
+    pub fn exits(&self, cell: CellPos) -> impl Iterator<Item = Direction> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter(move |&direction| !self.has_wall(cell, direction))
+    }
+
+    pub fn to_nested_walls(&self) -> NestedWalls {
+        let (height, width) = (self.height, self.width);
+        let wall = |row, col, direction| self.has_wall(CellPos::new(row, col), direction);
+        (
+            (0..height)
+                .map(|row| {
+                    (0..width - 1)
+                        .map(|col| wall(row, col, Direction::Right))
+                        .collect()
+                })
+                .collect(),
+            (0..height - 1)
+                .map(|row| {
+                    (0..width)
+                        .map(|col| wall(row, col, Direction::Down))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    // the rows have to have the lengths NestedWalls describes for a height by width maze
+    pub fn from_nested_walls(
+        height: usize,
+        width: usize,
+        walls: &NestedWalls,
+    ) -> Result<Self, MazeFormatError> {
+        validate_dimensions(height, width)?;
+
+        let (horizontal, vertical) = walls;
+        if horizontal.len() != height || horizontal.iter().any(|r| r.len() != width - 1) {
+            return Err(MazeFormatError::InvalidDimensions(format!(
+                "walls.0 should be {} rows of {} walls",
+                height,
+                width - 1
+            )));
+        }
+        if vertical.len() != height - 1 || vertical.iter().any(|r| r.len() != width) {
+            return Err(MazeFormatError::InvalidDimensions(format!(
+                "walls.1 should be {} rows of {} walls",
+                height - 1,
+                width
+            )));
+        }
+
+        let mut maze = Maze::closed(height, width);
+        for (index, &wall) in horizontal.iter().chain(vertical).flatten().enumerate() {
+            maze.set_bit(index, wall);
+        }
+        Ok(maze)
+    }
+
+    // the walls packed eight to a byte in the order above, lowest bit first
+    pub(super) fn packed_walls(&self) -> Vec<u8> {
+        let len = wall_count(self.height, self.width).div_ceil(8);
+        self.bits
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .take(len)
+            .collect()
+    }
+
+    // the other way around, the bytes have to be as many as packed_walls gives for this size
+    pub(super) fn from_packed_walls(height: usize, width: usize, bytes: &[u8]) -> Self {
+        let mut maze = Maze::closed(height, width);
+        for (word, chunk) in maze.bits.iter_mut().zip(bytes.chunks(8)) {
+            let mut le_bytes = [0; 8];
+            le_bytes[..chunk.len()].copy_from_slice(chunk);
+            // anything past the last wall is cleared again
+            *word &= u64::from_le_bytes(le_bytes);
+        }
+        maze
+    }
+
+    // where the given side of a square is in bits, None for the outer edges
+    pub(super) fn wall_index(&self, cell: CellPos, direction: Direction) -> Option<usize> {
+        if !self.contains(cell) {
+            return None;
+        }
+        let next = self.neighbour(cell, direction)?;
+        // a wall is stored at the top left of the two squares it separates
+        let first = cell.min(next);
+        match direction {
+            Direction::Left | Direction::Right => Some(first.row * (self.width - 1) + first.col),
+            Direction::Up | Direction::Down => {
+                Some(self.height * (self.width - 1) + first.row * self.width + first.col)
+            }
+        }
+    }
+
+    fn set_bit(&mut self, index: usize, wall: bool) {
+        let mask = 1 << (index % WORD_BITS);
+        if wall {
+            self.bits[index / WORD_BITS] |= mask;
+        } else {
+            self.bits[index / WORD_BITS] &= !mask;
+        }
+    }
+
+    // every side between two squares in the order of the bits, as the square above or to the
+    // left of it and the direction to the other square
+    fn inner_sides(&self) -> impl Iterator<Item = (CellPos, Direction)> {
+        let (height, width) = (self.height, self.width);
+        let between_columns = (0..height).flat_map(move |row| {
+            (0..width - 1).map(move |col| (CellPos::new(row, col), Direction::Right))
+        });
+        let between_rows = (0..height - 1).flat_map(move |row| {
+            (0..width).map(move |col| (CellPos::new(row, col), Direction::Down))
+        });
+        between_columns.chain(between_rows)
+    }
+}
+
+// number of walls between the squares of a maze, which is how many bits it takes to store.
+// Saturates rather than overflowing for nonsense dimensions, which files can claim to have
+pub(super) fn wall_count(height: usize, width: usize) -> usize {
+    height
+        .saturating_mul(width.saturating_sub(1))
+        .saturating_add(height.saturating_sub(1).saturating_mul(width))
+}
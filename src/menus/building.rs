@@ -0,0 +1,295 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use super::{spawn_menu_button, MenuAction, MenuButton};
+use crate::grid::run::StartRun;
+use crate::styles::{building_screen_style, get_stats_text_style, row_style, Theme};
+use crate::{MazeAlgorithm, MazeOptions, MazeSize};
+use into_the_magical_forest::maze_gen::{CellPos, GenerationEvent, GenerationSteps};
+
+#[derive(Component)]
+pub struct BuildingScreen;
+
+#[derive(Component)]
+pub struct BuildingLabel;
+
+// how many generation events are shown every second. MAZE_BUILD_SPEED sets where it starts, and
+// the slower and faster buttons or the up and down keys halve and double it
+#[derive(Resource)]
+pub struct BuildSpeed {
+    pub steps_per_second: f32,
+}
+impl BuildSpeed {
+    const MIN: f32 = 1.0;
+    const MAX: f32 = 4096.0;
+
+    pub fn from_env() -> Self {
+        let steps_per_second = std::env::var("MAZE_BUILD_SPEED")
+            .ok()
+            .and_then(|speed| speed.trim().parse().ok())
+            .unwrap_or(32.0);
+        BuildSpeed {
+            steps_per_second: f32::clamp(steps_per_second, Self::MIN, Self::MAX),
+        }
+    }
+
+    fn slower(&mut self) {
+        self.steps_per_second = (self.steps_per_second / 2.0).max(Self::MIN);
+    }
+
+    fn faster(&mut self) {
+        self.steps_per_second = (self.steps_per_second * 2.0).min(Self::MAX);
+    }
+}
+
+// the maze that's being generated, and the sprite for each of its squares
+#[derive(Resource)]
+pub struct Building {
+    steps: GenerationSteps,
+    size: MazeSize,
+    cells: Vec<Entity>,
+    joined: Vec<bool>,
+    joined_count: usize,
+    // distance between the middles of two squares and where the top left one is, in pixels
+    pitch: f32,
+    origin: Vec2,
+    // part of a step left over from the last frame, so that slow speeds still get anywhere
+    budget: f32,
+    finished: bool,
+}
+impl Building {
+    fn index(&self, cell: CellPos) -> usize {
+        cell.row * self.size.width + cell.col
+    }
+
+    fn sprite(&self, cell: CellPos) -> Entity {
+        self.cells[self.index(cell)]
+    }
+
+    fn centre(&self, cell: CellPos) -> Vec2 {
+        self.origin + Vec2::new(cell.col as f32, -(cell.row as f32)) * self.pitch
+    }
+}
+
+pub fn spawn_building_screen(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    options: MazeOptions,
+) {
+    let size = *options.size;
+    let window = window_query.get_single().unwrap();
+
+    // leave room for the text above and the buttons below
+    let pitch = f32::min(
+        window.width() * 0.8 / size.width as f32,
+        window.height() * 0.6 / size.height as f32,
+    );
+    let centre = Vec2::new(window.width(), window.height()) / 2.0;
+    let origin = centre
+        + Vec2::new(
+            -(size.width as f32 - 1.0) / 2.0,
+            (size.height as f32 - 1.0) / 2.0,
+        ) * pitch;
+
+    let mut building = Building {
+        steps: options.steps(),
+        size,
+        cells: Vec::with_capacity(size.width * size.height),
+        joined: vec![false; size.width * size.height],
+        joined_count: 0,
+        pitch,
+        origin,
+        budget: 0.0,
+        finished: false,
+    };
+    for row in 0..size.height {
+        for col in 0..size.width {
+            let position = building.centre(CellPos::new(row, col));
+            let cell = commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: theme.maze.unvisited_cell,
+                        custom_size: Some(Vec2::splat(pitch * 0.7)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(position.extend(0.0)),
+                    ..default()
+                },
+                BuildingScreen,
+            ));
+            building.cells.push(cell.id());
+        }
+    }
+    commands.insert_resource(building);
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: building_screen_style(&theme),
+                ..default()
+            },
+            BuildingScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section("", get_stats_text_style(&theme)),
+                BuildingLabel,
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: row_style(&theme),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for action in [
+                        MenuAction::SlowerBuild,
+                        MenuAction::FasterBuild,
+                        MenuAction::SkipBuild,
+                        MenuAction::StartGame,
+                        MenuAction::ToTitle,
+                    ] {
+                        spawn_menu_button(parent, &theme, action);
+                    }
+                });
+        });
+}
+
+// the generation steps and the sprite handles are only needed while the screen is up
+pub fn remove_building(mut commands: Commands) {
+    commands.remove_resource::<Building>();
+}
+
+// the buttons, and the keyboard for the same things: up and down change the speed, space skips
+// to the end and enter starts the game with the maze that was built
+pub fn control_building(
+    button_query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    keys: Res<Input<KeyCode>>,
+    mut speed: ResMut<BuildSpeed>,
+    mut building: ResMut<Building>,
+    mut start_writer: EventWriter<StartRun>,
+) {
+    let clicked = button_query
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Clicked)
+        .map(|(_, button)| button.action);
+    let pressed = [
+        (KeyCode::Down, MenuAction::SlowerBuild),
+        (KeyCode::Up, MenuAction::FasterBuild),
+        (KeyCode::Space, MenuAction::SkipBuild),
+        (KeyCode::Return, MenuAction::StartGame),
+    ]
+    .into_iter()
+    .filter(|(key, _)| keys.just_pressed(*key))
+    .map(|(_, action)| action);
+
+    for action in clicked.chain(pressed) {
+        match action {
+            MenuAction::SlowerBuild => speed.slower(),
+            MenuAction::FasterBuild => speed.faster(),
+            MenuAction::SkipBuild => building.budget = f32::INFINITY,
+            // the seed was picked on the new game screen, so this makes the same maze again
+            MenuAction::StartGame => start_writer.send(StartRun { regenerate: true }),
+            _ => {}
+        }
+    }
+}
+
+pub fn advance_building(
+    mut commands: Commands,
+    time: Res<Time>,
+    theme: Res<Theme>,
+    speed: Res<BuildSpeed>,
+    mut building: ResMut<Building>,
+    mut sprite_query: Query<&mut Sprite>,
+) {
+    if building.finished {
+        return;
+    }
+    building.budget += time.delta_seconds() * speed.steps_per_second;
+
+    while building.budget >= 1.0 {
+        building.budget -= 1.0;
+        let Some(event) = building.steps.next() else {
+            building.finished = true;
+            return;
+        };
+
+        let mut paint = |entity: Entity, color: Color| {
+            if let Ok(mut sprite) = sprite_query.get_mut(entity) {
+                sprite.color = color;
+            }
+        };
+        match event {
+            GenerationEvent::WalkStep { to, .. } => {
+                // the step that reaches the maze stays the colour of the maze
+                if !building.joined[building.index(to)] {
+                    paint(building.sprite(to), theme.maze.walk_cell);
+                }
+            }
+            GenerationEvent::LoopErased { erased, .. } => {
+                for cell in erased {
+                    paint(building.sprite(cell), theme.maze.unvisited_cell);
+                }
+            }
+            GenerationEvent::PathCarved { from, to } => {
+                // fills in the gap between the two squares
+                let position = (building.centre(from) + building.centre(to)) / 2.0;
+                let size = if from.row == to.row {
+                    Vec2::new(building.pitch * 0.3, building.pitch * 0.7)
+                } else {
+                    Vec2::new(building.pitch * 0.7, building.pitch * 0.3)
+                };
+                commands.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: theme.maze.joined_cell,
+                            custom_size: Some(size),
+                            ..default()
+                        },
+                        transform: Transform::from_translation(position.extend(0.0)),
+                        ..default()
+                    },
+                    BuildingScreen,
+                ));
+            }
+            GenerationEvent::CellJoined(cell) => {
+                paint(building.sprite(cell), theme.maze.joined_cell);
+                let index = building.index(cell);
+                building.joined[index] = true;
+                building.joined_count += 1;
+            }
+        }
+    }
+}
+
+pub fn update_building_label(
+    building: Res<Building>,
+    speed: Res<BuildSpeed>,
+    algorithm: Res<MazeAlgorithm>,
+    mut label_query: Query<&mut Text, With<BuildingLabel>>,
+) {
+    let squares = building.size.width * building.size.height;
+    let value = if building.finished {
+        format!(
+            "The {}x{} maze is ready, press enter to play it",
+            building.size.width, building.size.height
+        )
+    } else {
+        format!(
+            "Growing a {}x{} maze with {}: {} of {} squares\n{} steps a second, up and down to change, space to skip",
+            building.size.width,
+            building.size.height,
+            algorithm.0,
+            building.joined_count,
+            squares,
+            speed.steps_per_second
+        )
+    };
+
+    for mut text in label_query.iter_mut() {
+        // only touch the text when it changes, so it isn't laid out again every frame
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use bevy::{app::AppExit, ecs::schedule::SystemConfigs, prelude::*};
+
+mod building;
+use building::{
+    advance_building, control_building, remove_building, spawn_building_screen,
+    update_building_label, BuildSpeed, BuildingScreen,
+};
+mod new_game;
+use new_game::{
+    interact_with_setup, spawn_new_game_screen, type_seed, update_setup_labels, NewGameScreen,
+    SeedInput,
+};
+mod paused;
+use paused::{spawn_paused_screen, toggle_pause, PausedScreen};
+mod settings;
+use settings::{
+    interact_with_setting, spawn_settings_screen, update_setting_labels, SettingsScreen,
+};
+mod title;
+use title::{spawn_title_screen, TitleScreen};
+mod victory;
+use victory::{spawn_victory_screen, VictoryScreen};
+
+use crate::grid::run::StartRun;
+use crate::styles::{
+    button_style, get_button_text_style, get_title_text_style, main_menu_style, Restyle, Theme,
+};
+use crate::{AppState, MazeSeed};
+
+// the screens around the maze itself: title, new game, pause, victory and settings
+pub struct MenusPlugin;
+impl Plugin for MenusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SettingsReturn>()
+            .insert_resource(BuildSpeed::from_env())
+            .insert_resource(SeedInput::from_env())
+            .add_system(spawn_title_screen.in_schedule(OnEnter(AppState::Title)))
+            .add_system(despawn_screen::<TitleScreen>.in_schedule(OnExit(AppState::Title)))
+            .add_system(spawn_new_game_screen.in_schedule(OnEnter(AppState::NewGame)))
+            .add_system(despawn_screen::<NewGameScreen>.in_schedule(OnExit(AppState::NewGame)))
+            .add_system(spawn_paused_screen.in_schedule(OnEnter(AppState::Paused)))
+            .add_system(despawn_screen::<PausedScreen>.in_schedule(OnExit(AppState::Paused)))
+            .add_system(spawn_victory_screen.in_schedule(OnEnter(AppState::Victory)))
+            .add_system(despawn_screen::<VictoryScreen>.in_schedule(OnExit(AppState::Victory)))
+            .add_system(spawn_settings_screen.in_schedule(OnEnter(AppState::Settings)))
+            .add_system(despawn_screen::<SettingsScreen>.in_schedule(OnExit(AppState::Settings)))
+            .add_system(spawn_building_screen.in_schedule(OnEnter(AppState::Building)))
+            .add_system(despawn_screen::<BuildingScreen>.in_schedule(OnExit(AppState::Building)))
+            .add_system(remove_building.in_schedule(OnExit(AppState::Building)))
+            .add_systems(rebuild_on_restyle::<TitleScreen, _>(
+                AppState::Title,
+                spawn_title_screen,
+            ))
+            .add_systems(rebuild_on_restyle::<NewGameScreen, _>(
+                AppState::NewGame,
+                spawn_new_game_screen,
+            ))
+            .add_systems(rebuild_on_restyle::<PausedScreen, _>(
+                AppState::Paused,
+                spawn_paused_screen,
+            ))
+            .add_systems(rebuild_on_restyle::<VictoryScreen, _>(
+                AppState::Victory,
+                spawn_victory_screen,
+            ))
+            .add_systems(rebuild_on_restyle::<SettingsScreen, _>(
+                AppState::Settings,
+                spawn_settings_screen,
+            ))
+            .add_system(interact_with_menu_button)
+            .add_system(update_menu_button_colors)
+            .add_system(toggle_pause)
+            .add_systems(
+                (
+                    interact_with_setup,
+                    type_seed,
+                    update_setup_labels
+                        .after(interact_with_setup)
+                        .after(type_seed),
+                )
+                    .in_set(OnUpdate(AppState::NewGame)),
+            )
+            .add_systems(
+                (
+                    interact_with_setting,
+                    update_setting_labels.after(interact_with_setting),
+                )
+                    .in_set(OnUpdate(AppState::Settings)),
+            )
+            .add_systems(
+                (
+                    control_building,
+                    advance_building.after(control_building),
+                    update_building_label.after(advance_building),
+                )
+                    .in_set(OnUpdate(AppState::Building)),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MenuAction {
+    NewGame,
+    StartGame,
+    // starts the game from the new game screen by watching the maze being generated first
+    WatchBuild,
+    SlowerBuild,
+    FasterBuild,
+    // shows the rest of the maze being generated at once
+    SkipBuild,
+    Resume,
+    Settings,
+    // leaves the settings
+    Back,
+    ToTitle,
+    // the same maze again, from the start
+    Replay,
+    // a maze from a new random seed
+    NewMaze,
+    NarrowerMaze,
+    WiderMaze,
+    ShorterMaze,
+    TallerMaze,
+    CycleDifficulty,
+    CycleAlgorithm,
+    ToggleView,
+    ToggleBlockedExits,
+    ToggleBumpFeedback,
+    ToggleMinimap,
+    CycleTransition,
+    CycleTheme,
+    Quit,
+}
+impl MenuAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MenuAction::NewGame => "New game",
+            MenuAction::StartGame => "Start",
+            MenuAction::WatchBuild => "Watch build",
+            MenuAction::SlowerBuild => "Slower",
+            MenuAction::FasterBuild => "Faster",
+            MenuAction::SkipBuild => "Skip",
+            MenuAction::Resume => "Resume",
+            MenuAction::Settings => "Settings",
+            MenuAction::Back => "Back",
+            MenuAction::ToTitle => "Main menu",
+            MenuAction::Replay => "Replay",
+            MenuAction::NewMaze => "New maze",
+            MenuAction::NarrowerMaze | MenuAction::ShorterMaze => "-",
+            MenuAction::WiderMaze | MenuAction::TallerMaze => "+",
+            MenuAction::CycleDifficulty => "Difficulty",
+            MenuAction::CycleAlgorithm => "Algorithm",
+            MenuAction::ToggleView => "View",
+            MenuAction::ToggleBlockedExits => "Blocked exits",
+            MenuAction::ToggleBumpFeedback => "Bump",
+            MenuAction::ToggleMinimap => "Minimap",
+            MenuAction::CycleTransition => "Transition",
+            MenuAction::CycleTheme => "Theme",
+            MenuAction::Quit => "Quit",
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct MenuButton {
+    pub action: MenuAction,
+}
+
+// settings can be opened from both the title screen and the pause screen, and go back to
+// wherever they were opened from
+#[derive(Resource)]
+pub struct SettingsReturn(pub AppState);
+impl Default for SettingsReturn {
+    fn default() -> Self {
+        SettingsReturn(AppState::Title)
+    }
+}
+
+// removes everything a state spawned when the state is left
+pub fn despawn_screen<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// despawns what a state spawned and spawns it again from the new theme while the state is
+// showing, without leaving it, see styles::restyle_screen
+pub fn rebuild_on_restyle<T: Component, M>(
+    state: AppState,
+    spawn: impl IntoSystemConfig<M>,
+) -> SystemConfigs {
+    (despawn_screen::<T>, spawn)
+        .chain()
+        .distributive_run_if(on_event::<Restyle>())
+        .in_set(OnUpdate(state))
+}
+
+pub fn interact_with_menu_button(
+    button_query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut settings_return: ResMut<SettingsReturn>,
+    mut start_writer: EventWriter<StartRun>,
+    mut seed: ResMut<MazeSeed>,
+    mut exit_writer: EventWriter<AppExit>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        match button.action {
+            MenuAction::NewGame => next_state.set(AppState::NewGame),
+            MenuAction::Resume => next_state.set(AppState::Playing),
+            MenuAction::Settings => {
+                settings_return.0 = state.0;
+                next_state.set(AppState::Settings);
+            }
+            MenuAction::Back => next_state.set(settings_return.0),
+            MenuAction::ToTitle => next_state.set(AppState::Title),
+            MenuAction::Replay => start_writer.send(StartRun { regenerate: false }),
+            MenuAction::NewMaze => {
+                seed.0 = rand::random();
+                start_writer.send(StartRun { regenerate: true });
+            }
+            // the new game, building and settings screens handle the rest themselves, see
+            // new_game::interact_with_setup, building::control_building and
+            // settings::interact_with_setting
+            MenuAction::StartGame
+            | MenuAction::WatchBuild
+            | MenuAction::SlowerBuild
+            | MenuAction::FasterBuild
+            | MenuAction::SkipBuild
+            | MenuAction::NarrowerMaze
+            | MenuAction::WiderMaze
+            | MenuAction::ShorterMaze
+            | MenuAction::TallerMaze
+            | MenuAction::CycleDifficulty
+            | MenuAction::CycleAlgorithm
+            | MenuAction::ToggleView
+            | MenuAction::ToggleBlockedExits
+            | MenuAction::ToggleBumpFeedback
+            | MenuAction::ToggleMinimap
+            | MenuAction::CycleTransition
+            | MenuAction::CycleTheme => {}
+            MenuAction::Quit => exit_writer.send(AppExit),
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_menu_button_colors(
+    theme: Res<Theme>,
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<MenuButton>),
+    >,
+) {
+    for (interaction, mut background_color) in button_query.iter_mut() {
+        background_color.0 = match *interaction {
+            Interaction::Clicked => theme.buttons.pressed,
+            Interaction::Hovered => theme.buttons.hovered,
+            Interaction::None => theme.buttons.normal,
+        };
+    }
+}
+
+// a full screen column with everything centred, which all the menus are laid out in
+fn menu_root(theme: &Theme) -> NodeBundle {
+    NodeBundle {
+        style: main_menu_style(theme),
+        ..default()
+    }
+}
+
+fn spawn_heading(parent: &mut ChildBuilder, theme: &Theme, text: &str) {
+    parent.spawn(TextBundle::from_section(text, get_title_text_style(theme)));
+}
+
+fn spawn_menu_button(parent: &mut ChildBuilder, theme: &Theme, action: MenuAction) {
+    spawn_button(
+        parent,
+        theme,
+        button_style(theme),
+        action,
+        TextBundle::from_section(action.label(), get_button_text_style(theme)),
+    );
+}
+
+// a button with whatever text it should have, for buttons that show more than their action
+fn spawn_button(
+    parent: &mut ChildBuilder,
+    theme: &Theme,
+    style: Style,
+    action: MenuAction,
+    text: impl Bundle,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style,
+                background_color: theme.buttons.normal.into(),
+                ..default()
+            },
+            MenuButton { action },
+        ))
+        .with_children(|parent| {
+            parent.spawn(text);
+        });
+}
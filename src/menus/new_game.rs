@@ -0,0 +1,217 @@
+use bevy::prelude::*;
+
+use super::{menu_root, spawn_button, spawn_heading, spawn_menu_button, MenuAction, MenuButton};
+use crate::grid::run::StartRun;
+use crate::styles::{
+    get_button_text_style, get_seed_text_style, get_stats_text_style, row_label_style, row_style,
+    small_button_style, wide_button_style, Theme,
+};
+use crate::{AppState, Difficulty, MazeAlgorithm, MazeSeed, MazeSize};
+use into_the_magical_forest::maze_gen::{generator_name, GENERATOR_NAMES};
+
+#[derive(Component)]
+pub struct NewGameScreen;
+
+// the seed typed in on the new game screen. Left empty, every new game gets a random one.
+// MAZE_SEED fills it in to begin with
+#[derive(Resource, Default)]
+pub struct SeedInput(pub String);
+impl SeedInput {
+    pub fn from_env() -> Self {
+        match std::env::var("MAZE_SEED") {
+            Ok(value) => match value.trim().parse::<u64>() {
+                Ok(seed) => SeedInput(seed.to_string()),
+                Err(_) => {
+                    warn!("MAZE_SEED should be an unsigned 64 bit integer, ignoring {value:?}");
+                    SeedInput::default()
+                }
+            },
+            Err(_) => SeedInput::default(),
+        }
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.0.parse().ok()
+    }
+}
+
+// the text showing the current value of one of the options
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SetupLabel {
+    Width,
+    Height,
+    Difficulty,
+    Algorithm,
+    Seed,
+}
+
+pub fn spawn_new_game_screen(mut commands: Commands, theme: Res<Theme>) {
+    commands
+        .spawn((menu_root(&theme), NewGameScreen))
+        .with_children(|parent| {
+            spawn_heading(parent, &theme, "New game");
+
+            for (label, fewer, more) in [
+                (
+                    SetupLabel::Width,
+                    MenuAction::NarrowerMaze,
+                    MenuAction::WiderMaze,
+                ),
+                (
+                    SetupLabel::Height,
+                    MenuAction::ShorterMaze,
+                    MenuAction::TallerMaze,
+                ),
+            ] {
+                parent
+                    .spawn(NodeBundle {
+                        style: row_style(&theme),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        spawn_small_button(parent, &theme, fewer);
+                        parent.spawn((
+                            TextBundle::from_section("", get_stats_text_style(&theme))
+                                .with_style(row_label_style(&theme)),
+                            label,
+                        ));
+                        spawn_small_button(parent, &theme, more);
+                    });
+            }
+
+            for (label, action) in [
+                (SetupLabel::Difficulty, MenuAction::CycleDifficulty),
+                (SetupLabel::Algorithm, MenuAction::CycleAlgorithm),
+            ] {
+                spawn_button(
+                    parent,
+                    &theme,
+                    wide_button_style(&theme),
+                    action,
+                    (
+                        TextBundle::from_section("", get_button_text_style(&theme)),
+                        label,
+                    ),
+                );
+            }
+
+            parent.spawn((
+                TextBundle::from_section("", get_seed_text_style(&theme)),
+                SetupLabel::Seed,
+            ));
+
+            for action in [
+                MenuAction::StartGame,
+                MenuAction::WatchBuild,
+                MenuAction::ToTitle,
+            ] {
+                spawn_menu_button(parent, &theme, action);
+            }
+        });
+}
+
+fn spawn_small_button(parent: &mut ChildBuilder, theme: &Theme, action: MenuAction) {
+    spawn_button(
+        parent,
+        theme,
+        small_button_style(theme),
+        action,
+        TextBundle::from_section(action.label(), get_button_text_style(theme)),
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn interact_with_setup(
+    button_query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    mut size: ResMut<MazeSize>,
+    mut difficulty: ResMut<Difficulty>,
+    mut algorithm: ResMut<MazeAlgorithm>,
+    mut seed: ResMut<MazeSeed>,
+    seed_input: Res<SeedInput>,
+    mut start_writer: EventWriter<StartRun>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        match button.action {
+            MenuAction::NarrowerMaze => size.width = (size.width - 1).max(MazeSize::MIN),
+            MenuAction::WiderMaze => size.width = (size.width + 1).min(MazeSize::MAX),
+            MenuAction::ShorterMaze => size.height = (size.height - 1).max(MazeSize::MIN),
+            MenuAction::TallerMaze => size.height = (size.height + 1).min(MazeSize::MAX),
+            // picking a preset also picks its size, which can still be changed afterwards
+            MenuAction::CycleDifficulty => {
+                let next = Difficulty::ALL
+                    .iter()
+                    .position(|d| d == &*difficulty)
+                    .map_or(0, |i| (i + 1) % Difficulty::ALL.len());
+                *difficulty = Difficulty::ALL[next];
+                *size = difficulty.size();
+            }
+            MenuAction::CycleAlgorithm => {
+                // MAZE_ALGORITHM may spell the name differently, e.g. recursive_backtracker
+                let next = generator_name(&algorithm.0)
+                    .and_then(|current| GENERATOR_NAMES.iter().position(|&name| name == current))
+                    .map_or(0, |i| (i + 1) % GENERATOR_NAMES.len());
+                algorithm.0 = GENERATOR_NAMES[next].to_string();
+            }
+            MenuAction::StartGame => {
+                seed.0 = seed_input.seed().unwrap_or_else(rand::random);
+                start_writer.send(StartRun { regenerate: true });
+            }
+            MenuAction::WatchBuild => {
+                seed.0 = seed_input.seed().unwrap_or_else(rand::random);
+                next_state.set(AppState::Building);
+            }
+            _ => {}
+        }
+    }
+}
+
+// digits typed while on the new game screen go into the seed, backspace takes them out again
+pub fn type_seed(
+    mut character_reader: EventReader<ReceivedCharacter>,
+    keys: Res<Input<KeyCode>>,
+    mut seed_input: ResMut<SeedInput>,
+) {
+    for character in character_reader.iter() {
+        if character.char.is_ascii_digit() {
+            let typed = format!("{}{}", seed_input.0, character.char);
+            // anything that no longer fits in a u64 is dropped
+            if typed.parse::<u64>().is_ok() {
+                seed_input.0 = typed;
+            }
+        }
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        seed_input.0.pop();
+    }
+}
+
+pub fn update_setup_labels(
+    size: Res<MazeSize>,
+    difficulty: Res<Difficulty>,
+    algorithm: Res<MazeAlgorithm>,
+    seed_input: Res<SeedInput>,
+    mut label_query: Query<(&mut Text, &SetupLabel)>,
+) {
+    for (mut text, label) in label_query.iter_mut() {
+        let value = match label {
+            SetupLabel::Width => format!("width: {}", size.width),
+            SetupLabel::Height => format!("height: {}", size.height),
+            SetupLabel::Difficulty => format!("Difficulty: {}", difficulty.label()),
+            SetupLabel::Algorithm => format!("Algorithm: {}", algorithm.0),
+            SetupLabel::Seed => match seed_input.seed() {
+                Some(seed) => format!("seed: {seed} (backspace to edit)"),
+                None => String::from("seed: random (type a number to pick one)"),
+            },
+        };
+
+        // only touch the text when it changes, so it isn't laid out again every frame
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+use super::{menu_root, spawn_heading, spawn_menu_button, MenuAction};
+use crate::styles::Theme;
+use crate::AppState;
+
+#[derive(Component)]
+pub struct PausedScreen;
+
+pub fn spawn_paused_screen(mut commands: Commands, theme: Res<Theme>) {
+    commands
+        .spawn((menu_root(&theme), PausedScreen))
+        .with_children(|parent| {
+            spawn_heading(parent, &theme, "Paused");
+            for action in [
+                MenuAction::Resume,
+                MenuAction::Settings,
+                MenuAction::ToTitle,
+            ] {
+                spawn_menu_button(parent, &theme, action);
+            }
+        });
+}
+
+// escape pauses the game, and resumes it again from the pause screen
+pub fn toggle_pause(
+    keys: Res<Input<KeyCode>>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if !keys.just_pressed(KeyCode::Escape) {
+        return;
+    }
+
+    match state.0 {
+        AppState::Playing => next_state.set(AppState::Paused),
+        AppState::Paused => next_state.set(AppState::Playing),
+        _ => {}
+    }
+}
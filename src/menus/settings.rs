@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+
+use super::{menu_root, spawn_button, spawn_heading, spawn_menu_button, MenuAction, MenuButton};
+use crate::grid::layout::{BlockedExits, NavigationSettings, ViewMode};
+use crate::styles::{get_button_text_style, wide_button_style, Theme, Themes};
+
+#[derive(Component)]
+pub struct SettingsScreen;
+
+// the text on a setting's button, which shows its current value
+#[derive(Component)]
+pub struct SettingLabel {
+    action: MenuAction,
+}
+
+const SETTINGS: [MenuAction; 6] = [
+    MenuAction::ToggleView,
+    MenuAction::ToggleBlockedExits,
+    MenuAction::ToggleBumpFeedback,
+    MenuAction::ToggleMinimap,
+    MenuAction::CycleTransition,
+    MenuAction::CycleTheme,
+];
+
+pub fn spawn_settings_screen(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    settings: Res<NavigationSettings>,
+) {
+    commands
+        .spawn((menu_root(&theme), SettingsScreen))
+        .with_children(|parent| {
+            spawn_heading(parent, &theme, "Settings");
+
+            for action in SETTINGS {
+                spawn_button(
+                    parent,
+                    &theme,
+                    wide_button_style(&theme),
+                    action,
+                    (
+                        TextBundle::from_section(
+                            setting_label(action, &settings, &theme),
+                            get_button_text_style(&theme),
+                        ),
+                        SettingLabel { action },
+                    ),
+                );
+            }
+
+            spawn_menu_button(parent, &theme, MenuAction::Back);
+        });
+}
+
+pub fn interact_with_setting(
+    button_query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    mut settings: ResMut<NavigationSettings>,
+    mut themes: ResMut<Themes>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        match button.action {
+            MenuAction::ToggleView => {
+                settings.view = match settings.view {
+                    ViewMode::Rooms => ViewMode::TopDown,
+                    ViewMode::TopDown => ViewMode::Rooms,
+                };
+            }
+            MenuAction::ToggleBlockedExits => {
+                settings.blocked_exits = match settings.blocked_exits {
+                    BlockedExits::Disabled => BlockedExits::Hidden,
+                    BlockedExits::Hidden => BlockedExits::Disabled,
+                };
+            }
+            MenuAction::ToggleBumpFeedback => settings.bump_feedback = !settings.bump_feedback,
+            MenuAction::ToggleMinimap => settings.minimap = !settings.minimap,
+            MenuAction::CycleTransition => {
+                settings.transition.style = settings.transition.style.next();
+            }
+            // the screen is built again with the new theme once it has loaded, see
+            // styles::restyle_screen
+            MenuAction::CycleTheme => themes.next(),
+            _ => {}
+        }
+    }
+}
+
+pub fn update_setting_labels(
+    settings: Res<NavigationSettings>,
+    theme: Res<Theme>,
+    mut label_query: Query<(&mut Text, &SettingLabel)>,
+) {
+    if !settings.is_changed() && !theme.is_changed() {
+        return;
+    }
+
+    for (mut text, label) in label_query.iter_mut() {
+        text.sections[0].value = setting_label(label.action, &settings, &theme);
+    }
+}
+
+fn setting_label(action: MenuAction, settings: &NavigationSettings, theme: &Theme) -> String {
+    let value = match action {
+        MenuAction::ToggleView => match settings.view {
+            ViewMode::Rooms => "rooms",
+            ViewMode::TopDown => "top down",
+        },
+        MenuAction::ToggleBlockedExits => match settings.blocked_exits {
+            BlockedExits::Disabled => "greyed out",
+            BlockedExits::Hidden => "hidden",
+        },
+        MenuAction::ToggleBumpFeedback => on_off(settings.bump_feedback),
+        MenuAction::ToggleMinimap => on_off(settings.minimap),
+        MenuAction::CycleTransition => settings.transition.style.label(),
+        MenuAction::CycleTheme => &theme.name,
+        _ => "",
+    };
+    format!("{}: {value}", action.label())
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on {
+        "on"
+    } else {
+        "off"
+    }
+}
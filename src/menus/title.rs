@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+
+use super::{menu_root, spawn_heading, spawn_menu_button, MenuAction};
+use crate::styles::Theme;
+
+#[derive(Component)]
+pub struct TitleScreen;
+
+pub fn spawn_title_screen(mut commands: Commands, theme: Res<Theme>) {
+    commands
+        .spawn((menu_root(&theme), TitleScreen))
+        .with_children(|parent| {
+            spawn_heading(parent, &theme, "Into The Magical Forest");
+            for action in [MenuAction::NewGame, MenuAction::Settings, MenuAction::Quit] {
+                spawn_menu_button(parent, &theme, action);
+            }
+        });
+}
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::{menu_root, spawn_heading, spawn_menu_button, MenuAction};
+use crate::grid::player::RunStats;
+use crate::styles::{get_stats_text_style, Theme};
+
+#[derive(Component)]
+pub struct VictoryScreen;
+
+pub fn spawn_victory_screen(mut commands: Commands, theme: Res<Theme>, stats: Res<RunStats>) {
+    commands
+        .spawn((menu_root(&theme), VictoryScreen))
+        .with_children(|parent| {
+            spawn_heading(parent, &theme, "You made it!");
+
+            for line in [
+                format!("moves taken: {}", stats.moves),
+                format!("shortest way: {} moves", stats.optimal_moves),
+                format!("time: {}", format_time(stats.elapsed)),
+            ] {
+                parent.spawn(TextBundle::from_section(line, get_stats_text_style(&theme)));
+            }
+
+            for action in [MenuAction::Replay, MenuAction::NewMaze, MenuAction::ToTitle] {
+                spawn_menu_button(parent, &theme, action);
+            }
+        });
+}
+
+// minutes and seconds, e.g. 2:05
+fn format_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
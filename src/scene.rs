@@ -1,5 +1,229 @@
+use bevy::{
+    asset::{AssetLoader, AssetServerError, LoadContext, LoadedAsset},
+    audio::AudioSink,
+    ecs::system::SystemParam,
+    prelude::*,
+    reflect::TypeUuid,
+    time::common_conditions::on_timer,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::grid::layout::RefreshPage;
+use crate::grid::player::PlayerLocation;
+use crate::{hot_reload, AppState, MazeComponent, ASSET_RESCAN_INTERVAL};
+use into_the_magical_forest::maze_gen::CellPos;
+
+// where the scenes are loaded from, relative to the assets folder. Every .scene.ron or
+// .scene.json file in it is one scene
+const SCENE_FOLDER: &str = "scenes";
+
+// what a square, or a whole region of them, looks like on its page. Everything but the squares
+// can be left out, and falls back to what the page shows without a scene
+#[derive(Deserialize, TypeUuid, Clone, Debug)]
+#[uuid = "5b238ce7-e20b-49ee-8ee8-fbb80c4134a5"]
 pub struct Scene {
-    position: (usize, usize), // represents the coordinates of the scene instance
+    pub cells: SceneCells,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    // an image in the assets folder, used instead of the one from the backgrounds folder
+    #[serde(default)]
+    pub background: Option<String>,
+    // a sound in the assets folder, played on a loop for as long as the player is in the scene
+    #[serde(default)]
+    pub ambient_sound: Option<String>,
+    #[serde(default)]
+    pub objects: Vec<SceneObject>,
+}
+
+// the squares a scene is for, as x, y counted from 1, 1 in the bottom left corner the same way
+// the page shows them. A region includes both of its corners
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub enum SceneCells {
+    Cell(usize, usize),
+    Region {
+        from: (usize, usize),
+        to: (usize, usize),
+    },
+}
+impl SceneCells {
+    fn corners(&self) -> ((usize, usize), (usize, usize)) {
+        match *self {
+            SceneCells::Cell(x, y) => ((x, y), (x, y)),
+            SceneCells::Region { from, to } => (
+                (from.0.min(to.0), from.1.min(to.1)),
+                (from.0.max(to.0), from.1.max(to.1)),
+            ),
+        }
+    }
+
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        let ((left, bottom), (right, top)) = self.corners();
+        (left..=right).contains(&x) && (bottom..=top).contains(&y)
+    }
+
+    // in squares, the scene with the fewest of them wins where scenes overlap
+    pub fn area(&self) -> usize {
+        let ((left, bottom), (right, top)) = self.corners();
+        (right - left + 1) * (top - bottom + 1)
+    }
+}
+
+// something on the page that can be looked at, which shows its description when clicked
+#[derive(Deserialize, Clone, Debug)]
+pub struct SceneObject {
+    pub name: String,
+    pub description: String,
 }
 
-static scenes: Vec<Scene> = vec![];
+#[derive(Default)]
+pub struct SceneLoader;
+impl AssetLoader for SceneLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let json = load_context
+                .path()
+                .extension()
+                .is_some_and(|extension| extension == "json");
+            let scene: Scene = if json {
+                serde_json::from_slice(bytes)?
+            } else {
+                ron::de::from_bytes(bytes)?
+            };
+            load_context.set_default_asset(LoadedAsset::new(scene));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["scene.ron", "scene.json"]
+    }
+}
+
+// every scene in the scene folder. The handles keep them loaded
+#[derive(Resource, Default)]
+pub struct Scenes {
+    handles: Vec<Handle<Scene>>,
+}
+
+fn load_scene_folder(asset_server: &AssetServer) -> Result<Vec<Handle<Scene>>, AssetServerError> {
+    Ok(asset_server
+        .load_folder(SCENE_FOLDER)?
+        .into_iter()
+        .map(|handle| handle.typed::<Scene>())
+        .collect())
+}
+
+pub fn load_scenes(asset_server: Res<AssetServer>, mut scenes: ResMut<Scenes>) {
+    match load_scene_folder(&asset_server) {
+        Ok(handles) => scenes.handles = handles,
+        Err(error) => warn!("couldn't load the scenes in {SCENE_FOLDER}: {error}"),
+    }
+}
+
+// picks up scene files that were added while the game is running. The ones that were already
+// loaded are reloaded by the asset server when they change
+pub fn rescan_scenes(asset_server: Res<AssetServer>, mut scenes: ResMut<Scenes>) {
+    if let Ok(handles) = load_scene_folder(&asset_server) {
+        if handles != scenes.handles {
+            scenes.handles = handles;
+        }
+    }
+}
+
+// the page is built from the scenes, so it's built again whenever one of them is loaded, changed
+// or removed
+pub fn refresh_page_on_scene_change(
+    mut scene_events: EventReader<AssetEvent<Scene>>,
+    mut refresh_writer: EventWriter<RefreshPage>,
+) {
+    if scene_events.iter().count() > 0 {
+        refresh_writer.send(RefreshPage);
+    }
+}
+
+// finds the scene for a square among the ones that have finished loading
+#[derive(SystemParam)]
+pub struct SceneLookup<'w, 's> {
+    scenes: Res<'w, Scenes>,
+    assets: Res<'w, Assets<Scene>>,
+    maze_query: Query<'w, 's, &'static MazeComponent>,
+}
+impl SceneLookup<'_, '_> {
+    pub fn get(&self, cell: CellPos) -> Option<&Scene> {
+        let location = self.maze_query.get_single().ok()?.location(cell);
+        self.scenes
+            .handles
+            .iter()
+            .filter_map(|handle| self.assets.get(handle))
+            .filter(|scene| scene.cells.contains(location))
+            .min_by_key(|scene| scene.cells.area())
+    }
+}
+
+// the ambient sound that's playing, if any, so it's only restarted when it changes
+#[derive(Resource, Default)]
+pub struct AmbientSound {
+    path: Option<String>,
+    sink: Option<Handle<AudioSink>>,
+}
+impl AmbientSound {
+    fn stop(&mut self, sinks: &Assets<AudioSink>) {
+        if let Some(sink) = self.sink.take().and_then(|sink| sinks.get(&sink)) {
+            sink.stop();
+        }
+        self.path = None;
+    }
+}
+
+pub fn play_ambient_sound(
+    player_location: Res<PlayerLocation>,
+    lookup: SceneLookup,
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+    sinks: Res<Assets<AudioSink>>,
+    mut ambient: ResMut<AmbientSound>,
+) {
+    let path = lookup
+        .get(player_location.0)
+        .and_then(|scene| scene.ambient_sound.clone());
+    if path == ambient.path {
+        return;
+    }
+
+    ambient.stop(&sinks);
+    if let Some(path) = path {
+        let sink = audio.play_with_settings(asset_server.load(&path), PlaybackSettings::LOOP);
+        ambient.sink = Some(sinks.get_handle(sink));
+        ambient.path = Some(path);
+    }
+}
+
+pub fn stop_ambient_sound(sinks: Res<Assets<AudioSink>>, mut ambient: ResMut<AmbientSound>) {
+    ambient.stop(&sinks);
+}
+
+pub struct ScenePlugin;
+impl Plugin for ScenePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Scene>()
+            .init_asset_loader::<SceneLoader>()
+            .init_resource::<Scenes>()
+            .init_resource::<AmbientSound>()
+            .add_startup_system(load_scenes)
+            .add_system(
+                rescan_scenes
+                    .run_if(hot_reload)
+                    .run_if(on_timer(ASSET_RESCAN_INTERVAL)),
+            )
+            .add_system(refresh_page_on_scene_change)
+            .add_system(play_ambient_sound.in_set(OnUpdate(AppState::Playing)))
+            .add_system(stop_ambient_sound.in_schedule(OnExit(AppState::Playing)));
+    }
+}
@@ -0,0 +1,452 @@
+use bevy::{
+    asset::{AssetLoader, AssetServerError, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    time::common_conditions::on_timer,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{hot_reload, ASSET_RESCAN_INTERVAL};
+
+// where the themes are loaded from, relative to the assets folder. Every .theme.ron file in it is
+// one theme, and the settings cycle through them in the order of their file names
+const THEME_FOLDER: &str = "themes";
+// the theme that's used when UI_THEME doesn't name another one
+const DEFAULT_THEME: &str = "dark";
+
+// how every screen looks. The active theme is a resource, and each screen is built from it when
+// it's spawned
+#[derive(Resource, Deserialize, TypeUuid, Clone, Debug)]
+#[uuid = "0c0ae2a8-5a43-4c8f-a0f2-55a4b2e3f1c7"]
+pub struct Theme {
+    // shown on the settings screen
+    pub name: String,
+    // a font in the assets folder, used for all the text
+    pub font: String,
+    #[serde(skip)]
+    font_handle: Handle<Font>,
+    // behind everything, wherever nothing else is drawn
+    pub background: Color,
+    pub text: TextTheme,
+    pub buttons: ButtonTheme,
+    pub spacing: Spacing,
+    pub maze: MazeColors,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct TextTheme {
+    pub color: Color,
+    pub disabled_color: Color,
+    // the seed and the other small print
+    pub faint_color: Color,
+    pub title_size: f32,
+    pub button_size: f32,
+    pub stats_size: f32,
+    pub description_size: f32,
+    pub faint_size: f32,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ButtonTheme {
+    pub normal: Color,
+    pub hovered: Color,
+    pub pressed: Color,
+    pub disabled: Color,
+    pub width: f32,
+    pub height: f32,
+    // for buttons that show their value as well as what they do
+    pub wide_width: f32,
+}
+
+// in pixels
+#[derive(Deserialize, Clone, Debug)]
+pub struct Spacing {
+    // between the things stacked on a screen
+    pub gap: f32,
+    // between the things next to each other in a row
+    pub row_gap: f32,
+    // between the edges of the window and what's drawn along them
+    pub margin: f32,
+    pub title_width: f32,
+    pub title_height: f32,
+    pub row_label_width: f32,
+    pub description_width: f32,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct MazeColors {
+    // the squares of a maze being generated
+    pub unvisited_cell: Color,
+    pub walk_cell: Color,
+    pub joined_cell: Color,
+    // the top down view of the maze
+    pub floor: Color,
+    pub wall: Color,
+    pub player: Color,
+    pub goal: Color,
+    // the minimap in the corner of the rooms view
+    pub minimap_background: Color,
+    pub minimap_visited: Color,
+    pub minimap_current: Color,
+    pub minimap_wall: Color,
+}
+
+impl Theme {
+    // the same as themes/dark.theme.ron, used until the theme files have loaded
+    fn dark() -> Self {
+        Theme {
+            name: String::from("dark"),
+            font: String::from("fonts/FiraSans-Bold.ttf"),
+            font_handle: Handle::default(),
+            background: Color::rgb(0.0, 0.0, 0.0),
+            text: TextTheme {
+                color: Color::WHITE,
+                disabled_color: Color::DARK_GRAY,
+                faint_color: Color::GRAY,
+                title_size: 64.0,
+                button_size: 32.0,
+                stats_size: 28.0,
+                description_size: 24.0,
+                faint_size: 16.0,
+            },
+            buttons: ButtonTheme {
+                normal: Color::rgb(0.15, 0.15, 0.15),
+                hovered: Color::rgb(0.25, 0.25, 0.25),
+                pressed: Color::rgb(0.35, 0.75, 0.35),
+                disabled: Color::rgb(0.08, 0.08, 0.08),
+                width: 200.0,
+                height: 80.0,
+                wide_width: 420.0,
+            },
+            spacing: Spacing {
+                gap: 8.0,
+                row_gap: 16.0,
+                margin: 16.0,
+                title_width: 300.0,
+                title_height: 120.0,
+                row_label_width: 240.0,
+                description_width: 720.0,
+            },
+            maze: MazeColors {
+                unvisited_cell: Color::rgb(0.12, 0.12, 0.12),
+                walk_cell: Color::rgb(0.85, 0.7, 0.2),
+                joined_cell: Color::rgb(0.35, 0.75, 0.35),
+                floor: Color::rgb(0.1, 0.16, 0.1),
+                wall: Color::rgb(0.55, 0.45, 0.3),
+                player: Color::rgb(0.9, 0.9, 0.95),
+                goal: Color::rgb(0.35, 0.75, 0.35),
+                minimap_background: Color::rgba(0.0, 0.0, 0.0, 0.6),
+                minimap_visited: Color::rgb(0.3, 0.3, 0.3),
+                minimap_current: Color::rgb(0.9, 0.9, 0.95),
+                minimap_wall: Color::rgb(0.55, 0.45, 0.3),
+            },
+        }
+    }
+
+    fn text_style(&self, font_size: f32, color: Color) -> TextStyle {
+        TextStyle {
+            font: self.font_handle.clone(),
+            font_size,
+            color,
+        }
+    }
+}
+impl FromWorld for Theme {
+    fn from_world(world: &mut World) -> Self {
+        let mut theme = Theme::dark();
+        theme.font_handle = world.resource::<AssetServer>().load(&theme.font);
+        theme
+    }
+}
+
+pub fn main_menu_style(theme: &Theme) -> Style {
+    Style {
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+        gap: Size::all(Val::Px(theme.spacing.gap)),
+        ..default()
+    }
+}
+
+pub fn title_style(theme: &Theme) -> Style {
+    Style {
+        flex_direction: FlexDirection::Row,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        size: Size::new(
+            Val::Px(theme.spacing.title_width),
+            Val::Px(theme.spacing.title_height),
+        ),
+        ..default()
+    }
+}
+
+pub fn get_title_text_style(theme: &Theme) -> TextStyle {
+    theme.text_style(theme.text.title_size, theme.text.color)
+}
+
+fn sized_button_style(width: f32, height: f32) -> Style {
+    Style {
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        size: Size::new(Val::Px(width), Val::Px(height)),
+        ..default()
+    }
+}
+
+pub fn button_style(theme: &Theme) -> Style {
+    sized_button_style(theme.buttons.width, theme.buttons.height)
+}
+
+pub fn wide_button_style(theme: &Theme) -> Style {
+    sized_button_style(theme.buttons.wide_width, theme.buttons.height)
+}
+
+// square, as tall as the other buttons
+pub fn small_button_style(theme: &Theme) -> Style {
+    sized_button_style(theme.buttons.height, theme.buttons.height)
+}
+
+// a line of controls next to each other, like the - and + around a number
+pub fn row_style(theme: &Theme) -> Style {
+    Style {
+        flex_direction: FlexDirection::Row,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        gap: Size::all(Val::Px(theme.spacing.row_gap)),
+        ..default()
+    }
+}
+
+// keeps the number between the - and + buttons from moving them around as it changes
+pub fn row_label_style(theme: &Theme) -> Style {
+    Style {
+        justify_content: JustifyContent::Center,
+        size: Size::new(Val::Px(theme.spacing.row_label_width), Val::Auto),
+        ..default()
+    }
+}
+
+// keeps long scene descriptions from running off the sides of the page
+pub fn description_style(theme: &Theme) -> Style {
+    Style {
+        max_size: Size::new(Val::Px(theme.spacing.description_width), Val::Undefined),
+        ..default()
+    }
+}
+
+// the building screen keeps its text at the top and its buttons at the bottom, leaving the
+// middle free for the maze
+pub fn building_screen_style(theme: &Theme) -> Style {
+    Style {
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::SpaceBetween,
+        align_items: AlignItems::Center,
+        size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+        padding: UiRect::all(Val::Px(theme.spacing.margin)),
+        ..default()
+    }
+}
+
+// text in the top left corner, over whatever else is on the screen
+pub fn overlay_style(theme: &Theme) -> Style {
+    Style {
+        position_type: PositionType::Absolute,
+        position: UiRect {
+            left: Val::Px(theme.spacing.margin),
+            top: Val::Px(theme.spacing.margin),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+pub fn get_button_text_style(theme: &Theme) -> TextStyle {
+    theme.text_style(theme.text.button_size, theme.text.color)
+}
+
+pub fn get_disabled_button_text_style(theme: &Theme) -> TextStyle {
+    theme.text_style(theme.text.button_size, theme.text.disabled_color)
+}
+
+pub fn get_stats_text_style(theme: &Theme) -> TextStyle {
+    theme.text_style(theme.text.stats_size, theme.text.color)
+}
+
+pub fn get_description_text_style(theme: &Theme) -> TextStyle {
+    theme.text_style(theme.text.description_size, theme.text.color)
+}
+
+pub fn get_seed_text_style(theme: &Theme) -> TextStyle {
+    theme.text_style(theme.text.faint_size, theme.text.faint_color)
+}
+
+#[derive(Default)]
+pub struct ThemeLoader;
+impl AssetLoader for ThemeLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let theme: Theme = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(theme));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["theme.ron"]
+    }
+}
+
+// every theme in the theme folder, and which of them is picked. The handles keep them loaded
+#[derive(Resource, Default)]
+pub struct Themes {
+    handles: Vec<Handle<Theme>>,
+    current: usize,
+}
+impl Themes {
+    fn current(&self) -> Option<&Handle<Theme>> {
+        self.handles.get(self.current)
+    }
+
+    pub fn next(&mut self) {
+        if !self.handles.is_empty() {
+            self.current = (self.current + 1) % self.handles.len();
+        }
+    }
+}
+
+// the themes sorted by file name, along with the names they're picked by with UI_THEME
+fn load_theme_folder(
+    asset_server: &AssetServer,
+) -> Result<Vec<(String, Handle<Theme>)>, AssetServerError> {
+    let mut themes: Vec<(String, Handle<Theme>)> = asset_server
+        .load_folder(THEME_FOLDER)?
+        .into_iter()
+        .filter_map(|handle| {
+            let path = asset_server.get_handle_path(&handle)?;
+            let name = path
+                .path()
+                .file_name()?
+                .to_str()?
+                .strip_suffix(".theme.ron")?;
+            Some((name.to_string(), handle.typed::<Theme>()))
+        })
+        .collect();
+    themes.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(themes)
+}
+
+// UI_THEME picks the theme to start with by its file name without .theme.ron, like high-contrast
+pub fn load_themes(asset_server: Res<AssetServer>, mut themes: ResMut<Themes>) {
+    let found = match load_theme_folder(&asset_server) {
+        Ok(found) => found,
+        Err(error) => {
+            warn!("couldn't load the themes in {THEME_FOLDER}: {error}");
+            return;
+        }
+    };
+    let wanted = std::env::var("UI_THEME").unwrap_or_else(|_| String::from(DEFAULT_THEME));
+    themes.current = found
+        .iter()
+        .position(|(name, _)| *name == wanted)
+        .unwrap_or_else(|| {
+            let names: Vec<&str> = found.iter().map(|(name, _)| name.as_str()).collect();
+            warn!("unknown theme {wanted:?}, expected one of {names:?}");
+            0
+        });
+    themes.handles = found.into_iter().map(|(_, handle)| handle).collect();
+}
+
+// picks up theme files that were added while the game is running, keeping the one that's picked.
+// The ones that were already loaded are reloaded by the asset server when they change, and
+// apply_theme passes that on
+pub fn rescan_themes(asset_server: Res<AssetServer>, mut themes: ResMut<Themes>) {
+    let Ok(found) = load_theme_folder(&asset_server) else {
+        return;
+    };
+    let handles: Vec<Handle<Theme>> = found.into_iter().map(|(_, handle)| handle).collect();
+    if handles == themes.handles {
+        return;
+    }
+    let picked = themes.current().cloned();
+    themes.current = picked
+        .and_then(|picked| handles.iter().position(|handle| *handle == picked))
+        .unwrap_or(0);
+    themes.handles = handles;
+}
+
+// makes the picked theme the active one once it has loaded, and again whenever it's picked or its
+// file changes
+pub fn apply_theme(
+    mut theme_events: EventReader<AssetEvent<Theme>>,
+    themes: Res<Themes>,
+    assets: Res<Assets<Theme>>,
+    asset_server: Res<AssetServer>,
+    mut theme: ResMut<Theme>,
+) {
+    let Some(current) = themes.current() else {
+        return;
+    };
+    let mut loaded = false;
+    for event in theme_events.iter() {
+        if let AssetEvent::Created { handle } | AssetEvent::Modified { handle } = event {
+            loaded |= handle == current;
+        }
+    }
+    if !loaded && !themes.is_changed() {
+        return;
+    }
+
+    if let Some(picked) = assets.get(current) {
+        *theme = picked.clone();
+        theme.font_handle = asset_server.load(&theme.font);
+    }
+}
+
+// sent when the theme changes, for the screen that's showing to build its UI again
+pub struct Restyle;
+
+// every screen is built from the theme when its state is entered. Entering the state again would
+// also start the run's sound over, so the screen that's showing only despawns and spawns its UI,
+// see menus::rebuild_on_restyle. The building screen would start the maze over, so it keeps the
+// old theme until it's left
+pub fn restyle_screen(
+    theme: Res<Theme>,
+    mut clear_color: ResMut<ClearColor>,
+    mut restyle_writer: EventWriter<Restyle>,
+) {
+    if !theme.is_changed() {
+        return;
+    }
+    clear_color.0 = theme.background;
+
+    if !theme.is_added() {
+        restyle_writer.send(Restyle);
+    }
+}
+
+pub struct ThemePlugin;
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Theme>()
+            .init_asset_loader::<ThemeLoader>()
+            .init_resource::<Theme>()
+            .init_resource::<Themes>()
+            .add_event::<Restyle>()
+            .add_startup_system(load_themes)
+            .add_system(
+                rescan_themes
+                    .run_if(hot_reload)
+                    .run_if(on_timer(ASSET_RESCAN_INTERVAL)),
+            )
+            .add_system(apply_theme)
+            .add_system(restyle_screen.after(apply_theme));
+    }
+}
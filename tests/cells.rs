@@ -0,0 +1,158 @@
+mod common;
+
+use common::{all_connected, generated};
+use into_the_magical_forest::maze_gen::{
+    generator_from_name, generator_name, CellPos, Direction, GrowingTree, Maze, GENERATOR_NAMES,
+};
+
+fn cells(maze: &Maze) -> impl Iterator<Item = CellPos> + '_ {
+    (0..maze.height()).flat_map(move |row| (0..maze.width()).map(move |col| CellPos::new(row, col)))
+}
+
+// walks the whole maze through can_move alone and checks it agrees with the walls, the edges
+// and itself from both sides
+fn check_navigation(name: &str, maze: &Maze, height: usize, width: usize) {
+    assert_eq!(
+        (maze.height(), maze.width()),
+        (height, width),
+        "{name} swapped the dimensions"
+    );
+
+    let mut passages = 0;
+    for cell in cells(maze) {
+        for direction in Direction::ALL {
+            let neighbour = maze.neighbour(cell, direction);
+            let at_edge = match direction {
+                Direction::Up => cell.row == 0,
+                Direction::Right => cell.col == width - 1,
+                Direction::Down => cell.row == height - 1,
+                Direction::Left => cell.col == 0,
+            };
+            assert_eq!(
+                neighbour.is_none(),
+                at_edge,
+                "{name}: {cell:?} {direction:?}"
+            );
+
+            if maze.can_move(cell, direction) {
+                let next = neighbour.expect("moved off the edge of the maze");
+                assert!(
+                    maze.can_move(next, direction.opposite()),
+                    "{name}: can't get back from {next:?} to {cell:?}"
+                );
+                passages += 1;
+            }
+        }
+    }
+
+    // a perfect maze is a tree, every passage is counted once from each side
+    assert_eq!(
+        passages / 2,
+        height * width - 1,
+        "{name} is not a perfect maze"
+    );
+
+    let distances = maze.distances((0, 0));
+    for cell in cells(maze) {
+        assert!(
+            distances[cell.row][cell.col].is_some(),
+            "{name}: {cell:?} can't be reached"
+        );
+    }
+}
+
+#[test]
+fn single_row() {
+    for width in [1, 2, 7, 40] {
+        for (name, maze) in generated(1, width, 7) {
+            check_navigation(name, &maze, 1, width);
+        }
+    }
+}
+
+#[test]
+fn single_column() {
+    for height in [2, 7, 40] {
+        for (name, maze) in generated(height, 1, 7) {
+            check_navigation(name, &maze, height, 1);
+        }
+    }
+}
+
+#[test]
+fn large_rectangles() {
+    for (height, width) in [(30, 120), (120, 30)] {
+        for (name, maze) in generated(height, width, 7) {
+            check_navigation(name, &maze, height, width);
+        }
+    }
+}
+
+#[test]
+fn neighbours_stay_inside() {
+    let maze = Maze::from_seed(3, 5, 1);
+
+    let corner = maze.neighbours(CellPos::new(0, 0));
+    assert_eq!(
+        corner,
+        vec![
+            (Direction::Right, CellPos::new(0, 1)),
+            (Direction::Down, CellPos::new(1, 0)),
+        ]
+    );
+    assert_eq!(maze.neighbours(CellPos::new(2, 4)).len(), 2);
+    assert_eq!(maze.neighbours(CellPos::new(1, 2)).len(), 4);
+    assert!(!maze.contains(CellPos::new(3, 0)));
+    assert!(!maze.contains(CellPos::new(0, 5)));
+}
+
+#[test]
+fn directions_between_cells() {
+    let cell = CellPos::new(4, 4);
+    for direction in Direction::ALL {
+        let next = cell.step(direction).unwrap();
+        assert_eq!(Direction::between(cell, next), Some(direction));
+        assert_eq!(next.step(direction.opposite()), Some(cell));
+    }
+    assert_eq!(Direction::between(cell, CellPos::new(5, 5)), None);
+    assert_eq!(CellPos::new(0, 3).step(Direction::Up), None);
+    assert_eq!(CellPos::new(3, 0).step(Direction::Left), None);
+}
+
+#[test]
+fn generator_names_can_be_spelled_loosely() {
+    for name in GENERATOR_NAMES {
+        assert_eq!(generator_name(name), Some(name));
+        assert_eq!(generator_name(&name.to_uppercase()), Some(name));
+        assert_eq!(generator_name(&name.replace('-', "_")), Some(name));
+    }
+    assert_eq!(
+        generator_name(" Recursive Backtracker "),
+        Some("recursive-backtracker")
+    );
+    assert_eq!(generator_name("recursive"), None);
+    assert!(generator_from_name("hunt_and_kill").is_some());
+}
+
+#[test]
+fn growing_tree_survives_any_bias() {
+    for newest_bias in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, -3.0, 7.5] {
+        let generator = GrowingTree { newest_bias };
+        let maze = Maze::gen_with(&generator, 9, 13, 4);
+        check_navigation("growing-tree", &maze, 9, 13);
+        assert!(all_connected(&maze), "bias {newest_bias} left squares out");
+        // the steps go through the same code
+        assert!(Maze::steps_with(&generator, 9, 13, 4).count() > 0);
+    }
+
+    // anything that isn't a number falls back to the default bias
+    let nan = Maze::gen_with(
+        &GrowingTree {
+            newest_bias: f64::NAN,
+        },
+        9,
+        13,
+        4,
+    );
+    assert_eq!(nan, Maze::gen_with(&GrowingTree::default(), 9, 13, 4));
+}
@@ -0,0 +1,50 @@
+// fixtures shared by the test files. Not every file uses all of them
+#![allow(dead_code)]
+
+use into_the_magical_forest::maze_gen::{
+    generator_from_name, Loops, Maze, MazeRng, TextOverlays, TextStyle, GENERATOR_NAMES,
+};
+use rand::SeedableRng;
+
+// the underscore drawing, which the snapshots are written in and the parser reads
+pub fn drawing(maze: &Maze) -> String {
+    maze.render_text(TextStyle::Underscore, &TextOverlays::default())
+}
+
+// a seeded maze before and after adding loops with the same seed
+pub fn with_loops(height: usize, width: usize, seed: u64, loops: Loops) -> (Maze, Maze) {
+    let perfect = Maze::from_seed(height, width, seed);
+    let mut looped = perfect.clone();
+    looped.add_loops(loops, &mut MazeRng::seed_from_u64(seed));
+    (perfect, looped)
+}
+
+// perfect mazes have a single path between two squares, the braided ones have a choice
+pub fn mazes() -> Vec<Maze> {
+    [(9, 9, 1), (5, 20, 2), (30, 12, 3), (1, 17, 4), (20, 3, 5)]
+        .into_iter()
+        .flat_map(|(height, width, seed)| {
+            let (perfect, braided) = with_loops(height, width, seed, Loops::Braid(1.0));
+            [perfect, braided]
+        })
+        .collect()
+}
+
+// every generator on one size, from the same seed
+pub fn generated(
+    height: usize,
+    width: usize,
+    seed: u64,
+) -> impl Iterator<Item = (&'static str, Maze)> {
+    GENERATOR_NAMES.into_iter().map(move |name| {
+        let generator = generator_from_name(name).unwrap();
+        (
+            name,
+            Maze::gen_with(generator.as_ref(), height, width, seed),
+        )
+    })
+}
+
+pub fn all_connected(maze: &Maze) -> bool {
+    maze.distances((0, 0)).iter().flatten().all(Option::is_some)
+}
@@ -0,0 +1,145 @@
+use into_the_magical_forest::maze_gen::{ImageOverlays, ImageStyle, Maze, Rgb};
+
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+// the number of rectangles in the svg painted in a colour
+fn rects_filled(svg: &str, color: Rgb) -> usize {
+    let fill = format!("fill=\"{}\"", hex(color));
+    svg.lines()
+        .filter(|l| l.trim_start().starts_with("<rect") && l.contains(&fill))
+        .count()
+}
+
+fn overlays(maze: &Maze) -> ImageOverlays {
+    let goal = (maze.height() - 1, maze.width() - 1);
+    ImageOverlays {
+        solution: maze.shortest_path((0, 0), goal),
+        heatmap: None,
+        start: Some((0, 0)),
+        goal: Some(goal),
+    }
+}
+
+#[test]
+fn svg_is_the_size_of_the_maze() {
+    let style = ImageStyle::default();
+    let svg = Maze::from_seed(5, 8, 1).to_svg(&style, &ImageOverlays::default());
+
+    // 8 squares of 24 pixels across plus the outer wall, 5 down
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.contains("width=\"196\" height=\"124\""));
+    assert!(svg.contains("viewBox=\"0 0 196 124\""));
+    assert!(svg.trim_end().ends_with("</svg>"));
+}
+
+#[test]
+fn svg_draws_every_standing_wall() {
+    let style = ImageStyle::default();
+    for (height, width, seed) in [(5, 8, 1), (12, 12, 2), (1, 6, 3)] {
+        let maze = Maze::from_seed(height, width, seed);
+        let svg = maze.to_svg(&style, &ImageOverlays::default());
+
+        // one rectangle per inner wall plus the four sides
+        let walls = maze.inner_walls().count() + 4;
+        assert_eq!(rects_filled(&svg, style.wall_color), walls);
+        assert_eq!(rects_filled(&svg, style.background_color), 1);
+        assert_eq!(svg.matches("<rect").count(), walls + 1);
+    }
+
+    // a closed maze has a wall between every pair of squares next to each other
+    let closed = Maze::closed(3, 4);
+    let svg = closed.to_svg(&style, &ImageOverlays::default());
+    assert_eq!(rects_filled(&svg, style.wall_color), 3 * 3 + 2 * 4 + 4);
+}
+
+#[test]
+fn overlays_are_only_drawn_when_asked_for() {
+    let style = ImageStyle::default();
+    let maze = Maze::from_seed(7, 9, 4);
+
+    let plain = maze.to_svg(&style, &ImageOverlays::default());
+    for color in [style.path_color, style.start_color, style.goal_color] {
+        assert_eq!(rects_filled(&plain, color), 0);
+    }
+
+    let overlays = overlays(&maze);
+    let steps = overlays.solution.as_ref().unwrap().len() - 1;
+    let drawn = maze.to_svg(&style, &overlays);
+    assert_eq!(rects_filled(&drawn, style.path_color), steps);
+    assert_eq!(rects_filled(&drawn, style.start_color), 1);
+    assert_eq!(rects_filled(&drawn, style.goal_color), 1);
+
+    // just the markers, without the path
+    let markers = ImageOverlays {
+        solution: None,
+        ..overlays
+    };
+    let drawn = maze.to_svg(&style, &markers);
+    assert_eq!(rects_filled(&drawn, style.path_color), 0);
+    assert_eq!(rects_filled(&drawn, style.start_color), 1);
+
+    // the heatmap paints a square for every square it reaches
+    let heatmap = ImageOverlays {
+        heatmap: Some(maze.distances((0, 0))),
+        ..Default::default()
+    };
+    let drawn = maze.to_svg(&style, &heatmap);
+    assert_eq!(
+        drawn.matches("<rect").count(),
+        plain.matches("<rect").count() + 7 * 9
+    );
+    assert_eq!(rects_filled(&drawn, style.heatmap_colors.0), 1);
+}
+
+#[test]
+fn pixels_match_the_style() {
+    let style = ImageStyle::default();
+    let maze = Maze::from_seed(3, 4, 5);
+    let pixels = maze.to_pixels(&style, &ImageOverlays::default());
+
+    let (width, height) = (4 * 24 + 4, 3 * 24 + 4);
+    assert_eq!(pixels.len(), width * height * 3);
+    let pixel = |x: usize, y: usize| &pixels[(y * width + x) * 3..(y * width + x) * 3 + 3];
+
+    // the corners are wall, the middle of a square is background
+    assert_eq!(pixel(0, 0), style.wall_color);
+    assert_eq!(pixel(width - 1, height - 1), style.wall_color);
+    assert_eq!(pixel(14, 14), style.background_color);
+
+    // the start marker sits in the middle of its square
+    let overlays = overlays(&maze);
+    let pixels = maze.to_pixels(&style, &overlays);
+    assert_eq!(
+        &pixels[(14 * width + 14) * 3..(14 * width + 14) * 3 + 3],
+        style.start_color
+    );
+}
+
+#[test]
+fn png_decodes_to_the_same_image() {
+    let style = ImageStyle {
+        cell_size: 10,
+        wall_thickness: 2,
+        ..Default::default()
+    };
+    let maze = Maze::from_seed(6, 11, 6);
+    let overlays = overlays(&maze);
+
+    let mut png = Vec::new();
+    maze.write_png(&mut png, &style, &overlays).unwrap();
+
+    let decoder = png::Decoder::new(png.as_slice());
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut pixels).unwrap();
+
+    assert_eq!((frame.width, frame.height), (11 * 10 + 2, 6 * 10 + 2));
+    assert_eq!(frame.color_type, png::ColorType::Rgb);
+    assert_eq!(frame.bit_depth, png::BitDepth::Eight);
+    assert_eq!(
+        &pixels[..frame.buffer_size()],
+        maze.to_pixels(&style, &overlays).as_slice()
+    );
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::{all_connected, with_loops};
+use into_the_magical_forest::maze_gen::Loops;
+
+#[test]
+fn full_braid_leaves_no_dead_ends() {
+    for (height, width, seed) in [(2, 2, 0), (9, 9, 1), (6, 25, 2), (40, 3, 3)] {
+        let (perfect, braided) = with_loops(height, width, seed, Loops::Braid(1.0));
+        assert!(!perfect.dead_ends().is_empty());
+        assert_eq!(braided.dead_ends(), vec![], "{height}x{width}");
+        assert!(all_connected(&braided));
+    }
+}
+
+#[test]
+fn partial_braid_only_opens_walls() {
+    let (perfect, braided) = with_loops(15, 15, 4, Loops::Braid(0.5));
+    let dead_ends = perfect.dead_ends().len();
+    assert!(braided.dead_ends().len() < dead_ends);
+    assert!(braided.dead_ends().len() >= dead_ends / 2 - dead_ends / 4);
+    // every passage of the perfect maze is still there
+    assert!(perfect
+        .passages()
+        .all(|(cell, direction)| braided.can_move(cell, direction)));
+}
+
+#[test]
+fn removing_walls_takes_out_their_share() {
+    for share in [0.0, 0.1, 0.25, 1.0] {
+        let (perfect, looped) = with_loops(12, 20, 5, Loops::RemoveWalls(share));
+        let standing = perfect.inner_walls().count();
+        let expected = (standing as f64 * share).round() as usize;
+
+        assert_eq!(
+            looped.inner_walls().count(),
+            standing - expected,
+            "share {share}"
+        );
+        assert!(perfect
+            .passages()
+            .all(|(cell, direction)| looped.can_move(cell, direction)));
+        assert!(all_connected(&looped));
+    }
+}
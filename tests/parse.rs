@@ -0,0 +1,108 @@
+mod common;
+
+use common::{drawing, with_loops};
+use into_the_magical_forest::maze_gen::{Loops, Maze, ParseMazeError};
+
+#[test]
+fn drawings_parse_back_into_the_same_maze() {
+    for (height, width, seed) in [
+        (9, 9, 1),
+        (4, 11, 2),
+        (15, 3, 3),
+        (1, 8, 4),
+        (6, 1, 5),
+        (1, 1, 6),
+        (20, 35, 7),
+    ] {
+        let maze = Maze::from_seed(height, width, seed);
+        let parsed: Maze = drawing(&maze).parse().unwrap();
+        assert_eq!(parsed, maze, "{height}x{width} with seed {seed}");
+    }
+
+    // loops put passages next to each other, which the drawing has to tell apart
+    let (_, braided) = with_loops(10, 14, 8, Loops::RemoveWalls(0.5));
+    assert_eq!(drawing(&braided).parse::<Maze>().unwrap(), braided);
+}
+
+#[test]
+fn hand_drawn_mazes_parse() {
+    let drawing = concat!(" ________\n", "|__   |  |\n", "|________|\n");
+    let maze: Maze = drawing.parse().unwrap();
+    assert_eq!((maze.height(), maze.width()), (2, 3));
+    assert_eq!(maze.inner_walls().count(), 2);
+    // trailing whitespace and a missing final newline don't matter
+    assert_eq!(drawing.trim_end().parse::<Maze>().unwrap(), maze);
+    assert_eq!(format!("{drawing}\n\n").parse::<Maze>().unwrap(), maze);
+}
+
+#[test]
+fn bad_glyphs_say_where_they_are() {
+    let maze = Maze::from_seed(3, 4, 9);
+    let mut lines: Vec<String> = drawing(&maze).lines().map(String::from).collect();
+    lines[2].replace_range(5..6, "x");
+
+    match lines.join("\n").parse::<Maze>() {
+        Err(ParseMazeError::UnexpectedGlyph {
+            line,
+            column,
+            found,
+            ..
+        }) => assert_eq!((line, column, found), (3, 6, 'x')),
+        _ => panic!("expected an unexpected glyph error"),
+    }
+
+    // the floor of a square has to be the same glyph twice
+    match " _____\n|_    |\n|_____|".parse::<Maze>() {
+        Err(ParseMazeError::UnexpectedGlyph { line, column, .. }) => {
+            assert_eq!((line, column), (2, 3))
+        }
+        _ => panic!("expected an unexpected glyph error"),
+    }
+
+    // the bottom row needs its wall
+    match " _____\n|__|  |\n|__   |".parse::<Maze>() {
+        Err(ParseMazeError::UnexpectedGlyph { line, column, .. }) => {
+            assert_eq!((line, column), (3, 5))
+        }
+        _ => panic!("expected an unexpected glyph error"),
+    }
+}
+
+#[test]
+fn ragged_lines_are_rejected() {
+    match " _____\n|__|  |\n|_____|\n|__|".parse::<Maze>() {
+        Err(ParseMazeError::WrongLength {
+            line,
+            expected,
+            found,
+        }) => assert_eq!((line, expected, found), (4, 7, 4)),
+        _ => panic!("expected a wrong length error"),
+    }
+
+    // the top wall has to be a whole number of squares
+    assert!(matches!(
+        " _______\n|__|  |\n|_____|".parse::<Maze>(),
+        Err(ParseMazeError::WrongLength { line: 1, .. })
+    ));
+}
+
+#[test]
+fn empty_drawings_are_rejected() {
+    assert!(matches!("".parse::<Maze>(), Err(ParseMazeError::Empty)));
+    assert!(matches!(
+        "\n\n  \n".parse::<Maze>(),
+        Err(ParseMazeError::Empty)
+    ));
+    assert!(matches!(
+        " ______\n".parse::<Maze>(),
+        Err(ParseMazeError::Empty)
+    ));
+}
+
+#[test]
+fn missing_files_are_io_errors() {
+    assert!(matches!(
+        Maze::load_ascii("this/maze/does/not/exist.txt"),
+        Err(ParseMazeError::Io(_))
+    ));
+}
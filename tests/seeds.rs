@@ -0,0 +1,40 @@
+mod common;
+
+use common::drawing;
+use into_the_magical_forest::maze_gen::{Maze, Wilsons};
+
+// these were generated once and committed. If they stop matching, a change to the rng or to how
+// it's sampled has changed every seeded maze, and seeds from old bug reports no longer reproduce
+#[test]
+fn seeds_give_the_same_maze_everywhere() {
+    let square = concat!(
+        " __________________________\n",
+        "|__      |     |   _____   |\n",
+        "|__   |     |  |__    __|  |\n",
+        "|  |  |__|__|__   |   __|  |\n",
+        "|_____   |  |  |_____|__   |\n",
+        "|   ________       __|   __|\n",
+        "|__|     |  |  |  |_____   |\n",
+        "|__   |__    __|________|  |\n",
+        "|   __|  |__|  |      __|  |\n",
+        "|_____|___________|________|\n",
+    );
+    assert_eq!(drawing(&Maze::from_seed(9, 9, 42)), square);
+
+    let wide = concat!(
+        " ________________________________\n",
+        "|        |   __|   __|__      |  |\n",
+        "|  |  |   __    __|__      |  |  |\n",
+        "|__|  |__   |__|_____   |  |  |  |\n",
+        "|_____|_________________|__|_____|\n",
+    );
+    assert_eq!(drawing(&Maze::from_seed(4, 11, 7)), wide);
+}
+
+#[test]
+fn seeded_constructors_agree() {
+    let maze = Maze::from_seed(12, 7, 3);
+    assert_eq!(maze, Maze::from_seed(12, 7, 3));
+    assert_eq!(maze, Maze::gen_with(&Wilsons, 12, 7, 3));
+    assert_ne!(maze, Maze::from_seed(12, 7, 4));
+}
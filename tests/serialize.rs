@@ -0,0 +1,184 @@
+mod common;
+
+use common::mazes;
+use into_the_magical_forest::maze_gen::{Maze, MazeFormatError, FORMAT_VERSION};
+use serde_json::{json, Value};
+
+fn json_value(maze: &Maze) -> Value {
+    serde_json::from_str(&maze.to_json().unwrap()).unwrap()
+}
+
+fn from_value(value: Value) -> Result<Maze, MazeFormatError> {
+    Maze::from_json(&value.to_string())
+}
+
+#[test]
+fn mazes_survive_every_format() {
+    for maze in mazes().into_iter().chain([Maze::closed(1, 1)]) {
+        assert_eq!(Maze::from_json(&maze.to_json().unwrap()).unwrap(), maze);
+        assert_eq!(Maze::from_ron(&maze.to_ron().unwrap()).unwrap(), maze);
+        assert_eq!(Maze::from_bytes(&maze.to_bytes()).unwrap(), maze);
+    }
+}
+
+#[test]
+fn save_and_load_pick_the_format_from_the_extension() {
+    let maze = Maze::from_seed(6, 8, 5);
+    let dir = std::env::temp_dir().join(format!("maze-serialize-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    for name in ["maze.json", "maze.ron", "maze.bin"] {
+        let path = dir.join(name);
+        maze.save(&path).unwrap();
+        assert_eq!(Maze::load(&path).unwrap(), maze, "{name}");
+    }
+    assert!(std::fs::read(dir.join("maze.bin"))
+        .unwrap()
+        .starts_with(b"MAZE"));
+    assert!(std::fs::read_to_string(dir.join("maze.json"))
+        .unwrap()
+        .starts_with('{'));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn other_versions_are_rejected() {
+    let maze = Maze::from_seed(4, 4, 6);
+
+    let mut value = json_value(&maze);
+    value["version"] = json!(FORMAT_VERSION + 1);
+    assert!(matches!(
+        from_value(value),
+        Err(MazeFormatError::UnsupportedVersion(v)) if v == FORMAT_VERSION + 1
+    ));
+
+    let ron =
+        maze.to_ron()
+            .unwrap()
+            .replacen(&format!("version: {FORMAT_VERSION}"), "version: 0", 1);
+    assert!(matches!(
+        Maze::from_ron(&ron),
+        Err(MazeFormatError::UnsupportedVersion(0))
+    ));
+
+    let mut bytes = maze.to_bytes();
+    bytes[4..8].copy_from_slice(&7u32.to_le_bytes());
+    assert!(matches!(
+        Maze::from_bytes(&bytes),
+        Err(MazeFormatError::UnsupportedVersion(7))
+    ));
+}
+
+#[test]
+fn walls_have_to_match_the_dimensions() {
+    let maze = Maze::from_seed(5, 6, 7);
+
+    // the file claims a size the walls don't have
+    let mut value = json_value(&maze);
+    value["height"] = json!(6);
+    assert!(matches!(
+        from_value(value),
+        Err(MazeFormatError::InvalidDimensions(_))
+    ));
+
+    // one row of walls.0 is a wall short
+    let mut value = json_value(&maze);
+    value["walls"][0][2].as_array_mut().unwrap().pop();
+    assert!(matches!(
+        from_value(value),
+        Err(MazeFormatError::InvalidDimensions(_))
+    ));
+
+    // walls.1 is missing a row
+    let mut value = json_value(&maze);
+    value["walls"][1].as_array_mut().unwrap().pop();
+    assert!(matches!(
+        from_value(value),
+        Err(MazeFormatError::InvalidDimensions(_))
+    ));
+}
+
+#[test]
+fn zero_dimensions_are_rejected() {
+    let mut value = json_value(&Maze::from_seed(3, 3, 8));
+    value["width"] = json!(0);
+    assert!(matches!(
+        from_value(value),
+        Err(MazeFormatError::InvalidDimensions(_))
+    ));
+
+    let mut bytes = Maze::from_seed(3, 3, 8).to_bytes();
+    bytes[8..12].copy_from_slice(&0u32.to_le_bytes());
+    assert!(matches!(
+        Maze::from_bytes(&bytes),
+        Err(MazeFormatError::InvalidDimensions(_))
+    ));
+}
+
+#[test]
+fn binary_has_to_be_the_right_length() {
+    let bytes = Maze::from_seed(10, 10, 9).to_bytes();
+
+    let truncated = &bytes[..bytes.len() - 1];
+    assert!(matches!(
+        Maze::from_bytes(truncated),
+        Err(MazeFormatError::WrongLength { expected, found })
+            if expected == bytes.len() && found == bytes.len() - 1
+    ));
+
+    let mut extended = bytes.clone();
+    extended.push(0);
+    assert!(matches!(
+        Maze::from_bytes(&extended),
+        Err(MazeFormatError::WrongLength { expected, found })
+            if expected == bytes.len() && found == bytes.len() + 1
+    ));
+
+    // a huge size in the header should fail the length check, not try to allocate it
+    let mut huge = bytes.clone();
+    huge[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+    huge[12..16].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(
+        Maze::from_bytes(&huge),
+        Err(MazeFormatError::WrongLength { .. })
+    ));
+}
+
+#[test]
+fn binary_needs_the_magic_bytes() {
+    let mut bytes = Maze::from_seed(3, 5, 10).to_bytes();
+    bytes[0] = b'm';
+    assert!(matches!(
+        Maze::from_bytes(&bytes),
+        Err(MazeFormatError::NotAMaze)
+    ));
+
+    // too short to even hold the header
+    assert!(matches!(
+        Maze::from_bytes(b"MAZE"),
+        Err(MazeFormatError::NotAMaze)
+    ));
+    assert!(matches!(
+        Maze::from_bytes(&[]),
+        Err(MazeFormatError::NotAMaze)
+    ));
+}
+
+#[test]
+fn broken_text_reports_the_parser_error() {
+    assert!(matches!(
+        Maze::from_json("{\"version\": 1"),
+        Err(MazeFormatError::Json(_))
+    ));
+    assert!(matches!(
+        Maze::from_ron("(version: 1"),
+        Err(MazeFormatError::Ron(_))
+    ));
+
+    // the position of a ron error is where it went wrong in the input
+    match Maze::from_ron("(\n    version: 1,\n    height: x,\n)") {
+        Err(MazeFormatError::Ron(e)) => assert_eq!(e.position.line, 3),
+        _ => panic!("expected a ron error"),
+    }
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::mazes;
+use into_the_magical_forest::maze_gen::{CellPos, Direction, Maze};
+
+// every step of a path has to go through an open passage to the square next door
+fn check_path(maze: &Maze, path: &[(usize, usize)], from: (usize, usize), to: (usize, usize)) {
+    assert_eq!(path.first(), Some(&from));
+    assert_eq!(path.last(), Some(&to));
+    for step in path.windows(2) {
+        let (a, b) = (CellPos::from(step[0]), CellPos::from(step[1]));
+        let direction = Direction::between(a, b).expect("the path jumped a square");
+        assert!(maze.can_move(a, direction), "the path goes through a wall");
+    }
+}
+
+#[test]
+fn bfs_and_a_star_agree() {
+    for maze in mazes() {
+        let (height, width) = (maze.height(), maze.width());
+        let from = (0, 0);
+        let distances = maze.distances(from);
+
+        for to in [
+            (height - 1, width - 1),
+            (height / 2, width / 2),
+            (0, width - 1),
+        ] {
+            let bfs = maze.shortest_path(from, to).unwrap();
+            let a_star = maze.a_star(from, to).unwrap();
+            check_path(&maze, &bfs, from, to);
+            check_path(&maze, &a_star, from, to);
+
+            assert_eq!(bfs.len(), a_star.len());
+            assert_eq!(distances[to.0][to.1].unwrap() + 1, bfs.len());
+            assert!(maze.is_connected(from, to));
+        }
+    }
+}
+
+#[test]
+fn farthest_square_is_the_furthest_distance() {
+    for maze in mazes() {
+        let distances = maze.distances((0, 0));
+        let furthest = distances.iter().flatten().flatten().copied().max().unwrap();
+
+        let (square, distance) = maze.farthest_from((0, 0));
+        assert_eq!(distance, furthest);
+        assert_eq!(distances[square.0][square.1], Some(distance));
+    }
+}
+
+#[test]
+fn closed_off_squares_cant_be_reached() {
+    let maze = Maze::closed(4, 6);
+    assert_eq!(maze.shortest_path((0, 0), (3, 5)), None);
+    assert_eq!(maze.a_star((0, 0), (3, 5)), None);
+    assert!(!maze.is_connected((0, 0), (0, 1)));
+    assert_eq!(maze.farthest_from((2, 2)), ((2, 2), 0));
+
+    let distances = maze.distances((1, 1));
+    assert_eq!(distances[1][1], Some(0));
+    assert_eq!(distances.iter().flatten().flatten().count(), 1);
+
+    // a path from a square to itself is just that square
+    assert_eq!(maze.shortest_path((1, 1), (1, 1)), Some(vec![(1, 1)]));
+}
+
+#[test]
+fn squares_outside_the_maze_have_no_paths() {
+    let maze = Maze::from_seed(6, 9, 4);
+    for outside in [(6, 0), (0, 9), (6, 9), (usize::MAX, 2)] {
+        assert_eq!(maze.shortest_path(outside, (0, 0)), None);
+        assert_eq!(maze.shortest_path((0, 0), outside), None);
+        assert_eq!(maze.a_star(outside, (5, 8)), None);
+        assert_eq!(maze.a_star((5, 8), outside), None);
+        assert!(!maze.is_connected((0, 0), outside));
+
+        let distances = maze.distances(outside);
+        assert_eq!((distances.len(), distances[0].len()), (6, 9));
+        assert!(distances.iter().flatten().all(Option::is_none));
+        assert_eq!(maze.farthest_from(outside), (outside, 0));
+    }
+}
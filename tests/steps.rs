@@ -0,0 +1,236 @@
+use std::collections::HashSet;
+
+use into_the_magical_forest::maze_gen::{
+    generator_from_name, CellPos, Direction, GenerationEvent, Maze, Wilsons, GENERATOR_NAMES,
+};
+
+// building a maze from its steps has to end up with the maze generate makes
+#[test]
+fn steps_build_the_generated_maze() {
+    for name in GENERATOR_NAMES {
+        let generator = generator_from_name(name).unwrap();
+        for (height, width, seed) in [(1, 1, 0), (1, 9, 1), (8, 1, 2), (12, 17, 3)] {
+            let mut built = Maze::closed(height, width);
+            for event in Maze::steps_with(generator.as_ref(), height, width, seed) {
+                built.apply(&event);
+            }
+            let generated = Maze::gen_with(generator.as_ref(), height, width, seed);
+            assert_eq!(
+                built, generated,
+                "{name} built a different {height}x{width} maze"
+            );
+        }
+    }
+}
+
+// the generators that grow the maze out from a single square. The others build it in several
+// pieces that get joined up later
+const GROWING: [&str; 6] = [
+    "wilsons",
+    "recursive-backtracker",
+    "prims",
+    "aldous-broder",
+    "hunt-and-kill",
+    "growing-tree",
+];
+
+// every square joins once, and only ever onto the maze through a single carved wall
+#[test]
+fn squares_join_the_maze_once() {
+    for name in GROWING {
+        let generator = generator_from_name(name).unwrap();
+        let (height, width) = (15, 11);
+        let mut joined = HashSet::new();
+        let mut carving = None;
+
+        for event in Maze::steps_with(generator.as_ref(), height, width, 5) {
+            match event {
+                GenerationEvent::PathCarved { from, to } => {
+                    assert!(
+                        Direction::between(from, to).is_some(),
+                        "{name}: {from:?} {to:?}"
+                    );
+                    assert!(
+                        joined.contains(&to),
+                        "{name} carved to {to:?} outside the maze"
+                    );
+                    assert!(!joined.contains(&from), "{name} carved a loop at {from:?}");
+                    carving = Some(from);
+                }
+                GenerationEvent::CellJoined(cell) => {
+                    // only the very first square joins without a wall coming down for it
+                    if !joined.is_empty() {
+                        assert_eq!(carving.take(), Some(cell), "{name} joined {cell:?}");
+                    }
+                    assert!(joined.insert(cell), "{name} joined {cell:?} twice");
+                }
+                _ => {}
+            }
+        }
+
+        assert_eq!(joined.len(), height * width, "{name} left squares out");
+    }
+}
+
+// every square joins once, right after the first wall around it comes down, and every wall that
+// comes down joins two pieces that weren't connected yet
+#[test]
+fn pieces_join_up_without_loops() {
+    for name in GENERATOR_NAMES {
+        let generator = generator_from_name(name).unwrap();
+        let (height, width) = (13, 16);
+        // the piece of the maze each square is in, squares that haven't joined have none
+        let mut pieces: Vec<Vec<Option<usize>>> = vec![vec![None; width]; height];
+        let mut carved = Vec::new();
+        let mut carves = 0;
+
+        for event in Maze::steps_with(generator.as_ref(), height, width, 9) {
+            match event {
+                GenerationEvent::PathCarved { from, to } => {
+                    assert!(
+                        Direction::between(from, to).is_some(),
+                        "{name}: {from:?} {to:?}"
+                    );
+                    let (a, b) = (pieces[from.row][from.col], pieces[to.row][to.col]);
+                    assert!(a.is_none() || a != b, "{name} carved a loop at {from:?}");
+                    carved = vec![from, to];
+                    carves += 1;
+
+                    // the two pieces become one
+                    let piece = a.or(b).unwrap_or(carves);
+                    for square in pieces.iter_mut().flatten() {
+                        if square.is_some() && (*square == a || *square == b) {
+                            *square = Some(piece);
+                        }
+                    }
+                    for cell in [from, to] {
+                        if pieces[cell.row][cell.col].is_some() {
+                            pieces[cell.row][cell.col] = Some(piece);
+                        }
+                    }
+                }
+                GenerationEvent::CellJoined(cell) => {
+                    let joined = pieces.iter().flatten().any(Option::is_some);
+                    assert!(
+                        !joined || carved.contains(&cell),
+                        "{name} joined {cell:?} without carving to it"
+                    );
+                    assert!(
+                        pieces[cell.row][cell.col].is_none(),
+                        "{name} joined {cell:?} twice"
+                    );
+                    let piece = carved
+                        .iter()
+                        .find_map(|other| pieces[other.row][other.col])
+                        .unwrap_or(carves);
+                    pieces[cell.row][cell.col] = Some(piece);
+                }
+                _ => {}
+            }
+        }
+
+        assert_eq!(carves, height * width - 1, "{name} carved too many walls");
+        let first = pieces[0][0];
+        assert!(
+            pieces
+                .iter()
+                .flatten()
+                .all(|&piece| piece.is_some() && piece == first),
+            "{name} left squares out"
+        );
+    }
+}
+
+// each generator builds the maze its own way, rather than all of them replaying it from a corner
+#[test]
+fn generators_have_their_own_order() {
+    let orders: Vec<Vec<(CellPos, CellPos)>> = GENERATOR_NAMES
+        .into_iter()
+        .map(|name| {
+            let generator = generator_from_name(name).unwrap();
+            Maze::steps_with(generator.as_ref(), 10, 10, 3)
+                .filter_map(|event| match event {
+                    GenerationEvent::PathCarved { from, to } => Some((from, to)),
+                    _ => None,
+                })
+                .collect()
+        })
+        .collect();
+
+    for (i, a) in orders.iter().enumerate() {
+        for (j, b) in orders.iter().enumerate().skip(i + 1) {
+            assert_ne!(
+                a, b,
+                "{} and {} carved the walls in the same order",
+                GENERATOR_NAMES[i], GENERATOR_NAMES[j]
+            );
+        }
+    }
+
+    // the binary tree and the sidewinder go row by row from the top
+    for name in ["binary-tree", "sidewinder"] {
+        let generator = generator_from_name(name).unwrap();
+        let first = Maze::steps_with(generator.as_ref(), 10, 10, 3).next();
+        assert!(
+            matches!(first, Some(GenerationEvent::PathCarved { from, to }) if from.row == 0 && to.row == 0),
+            "{name} started with {first:?}"
+        );
+    }
+}
+
+// the random walks move one square at a time and only erase squares that are on them
+#[test]
+fn wilsons_walks_erase_their_loops() {
+    let (height, width) = (20, 20);
+    let mut walk: Vec<CellPos> = Vec::new();
+    let mut joined = HashSet::new();
+    let mut erased_loops = 0;
+
+    for event in Maze::steps_with(&Wilsons, height, width, 11) {
+        match event {
+            GenerationEvent::WalkStep { from: None, to } => {
+                assert!(
+                    walk.is_empty(),
+                    "a new walk started before the last one joined"
+                );
+                assert!(
+                    !joined.contains(&to),
+                    "walk started inside the maze at {to:?}"
+                );
+                walk.push(to);
+            }
+            GenerationEvent::WalkStep {
+                from: Some(from),
+                to,
+            } => {
+                assert_eq!(walk.last(), Some(&from), "the walk jumped from {from:?}");
+                assert!(Direction::between(from, to).is_some(), "{from:?} to {to:?}");
+                // stepping back onto the walk makes a loop, which is erased next
+                if !walk.contains(&to) {
+                    walk.push(to);
+                }
+            }
+            GenerationEvent::LoopErased { at, erased } => {
+                let keep = walk.iter().position(|&cell| cell == at).unwrap() + 1;
+                assert_eq!(walk.split_off(keep), erased);
+                erased_loops += 1;
+            }
+            GenerationEvent::PathCarved { .. } => {}
+            GenerationEvent::CellJoined(cell) => {
+                joined.insert(cell);
+                walk.retain(|&square| !joined.contains(&square));
+            }
+        }
+
+        // the walk never crosses itself once the loops are erased
+        let unique: HashSet<_> = walk.iter().collect();
+        assert_eq!(unique.len(), walk.len(), "the walk crosses itself");
+    }
+
+    assert!(walk.is_empty());
+    assert_eq!(joined.len(), height * width);
+    assert!(
+        erased_loops > 0,
+        "a maze this big always has some loops to erase"
+    );
+}
@@ -0,0 +1,206 @@
+mod common;
+
+use common::with_loops;
+use into_the_magical_forest::maze_gen::{Loops, Maze, MazeRng, TextOverlays, TextStyle};
+use rand::SeedableRng;
+
+fn render(maze: &Maze, style: TextStyle) -> String {
+    maze.render_text(style, &TextOverlays::default())
+}
+
+// display_maze as it was before the renderers, straight from the nested walls
+fn old_display_maze(maze: &Maze) -> String {
+    let walls = maze.to_nested_walls();
+    let height = walls.0.len();
+    let width = walls.1[0].len();
+
+    let mut text = String::from(" ");
+    for _ in 0..width - 1 {
+        text.push_str("___");
+    }
+    text.push_str("__\n");
+
+    for h in 0..height {
+        text.push('|');
+        for w in 0..width {
+            if h < height - 1 {
+                text.push_str(if walls.1[h][w] { "__" } else { "  " });
+            } else {
+                text.push_str("__");
+            }
+
+            if w < width - 1 {
+                if walls.0[h][w] {
+                    text.push('|');
+                } else if (h < height - 1 && walls.1[h][w] && walls.1[h][w + 1]) || h == height - 1
+                {
+                    text.push('_');
+                } else {
+                    text.push(' ');
+                }
+            }
+        }
+        text.push_str("|\n");
+    }
+
+    text
+}
+
+#[test]
+fn underscore_matches_the_old_display_maze() {
+    for (height, width, seed) in [(9, 9, 1), (4, 11, 2), (15, 3, 3), (2, 2, 4), (30, 40, 5)] {
+        let (maze, braided) = with_loops(height, width, seed, Loops::RemoveWalls(0.3));
+        assert_eq!(
+            render(&maze, TextStyle::Underscore),
+            old_display_maze(&maze)
+        );
+
+        assert_eq!(
+            render(&braided, TextStyle::Underscore),
+            old_display_maze(&braided)
+        );
+    }
+}
+
+#[test]
+fn underscore_snapshot() {
+    let expected = concat!(
+        " ______________\n",
+        "|   __    _____|\n",
+        "|  |________|  |\n",
+        "|  |  |     |  |\n",
+        "|________|_____|\n",
+    );
+    assert_eq!(
+        render(&Maze::from_seed(4, 5, 0), TextStyle::Underscore),
+        expected
+    );
+}
+
+#[test]
+fn box_drawing_snapshot() {
+    let expected = concat!(
+        "┌───────┬───────────┐\n",
+        "│       │           │\n",
+        "│   ╶───┼───────╴   │\n",
+        "│       │           │\n",
+        "│   ╶───┴───╴   ╶───┤\n",
+        "│                   │\n",
+        "├───╴   ╶───┬───╴   │\n",
+        "│           │       │\n",
+        "└───────────┴───────┘\n",
+    );
+    assert_eq!(
+        render(&Maze::from_seed(4, 5, 20), TextStyle::BoxDrawing),
+        expected
+    );
+}
+
+#[test]
+fn box_drawing_joins_every_wall() {
+    let expected = concat!(
+        "┌───┬───┐\n",
+        "│   │   │\n",
+        "├───┼───┤\n",
+        "│   │   │\n",
+        "└───┴───┘\n",
+    );
+    assert_eq!(render(&Maze::closed(2, 2), TextStyle::BoxDrawing), expected);
+
+    // with no walls inside only the outline is left
+    let mut open = Maze::closed(2, 2);
+    open.add_loops(Loops::RemoveWalls(1.0), &mut MazeRng::seed_from_u64(0));
+    let expected = concat!(
+        "┌───────┐\n",
+        "│       │\n",
+        "│       │\n",
+        "│       │\n",
+        "└───────┘\n",
+    );
+    assert_eq!(render(&open, TextStyle::BoxDrawing), expected);
+}
+
+#[test]
+fn blocks_snapshot() {
+    let expected = concat!(
+        "██████████████████████\n",
+        "██                  ██\n",
+        "██  ██████  ██████████\n",
+        "██  ██          ██  ██\n",
+        "██  ██████████████  ██\n",
+        "██  ██  ██      ██  ██\n",
+        "██  ██  ██  ██  ██  ██\n",
+        "██          ██      ██\n",
+        "██████████████████████\n",
+    );
+    assert_eq!(
+        render(&Maze::from_seed(4, 5, 0), TextStyle::Blocks),
+        expected
+    );
+}
+
+#[test]
+fn overlays_mark_their_squares() {
+    let maze = Maze::from_seed(3, 4, 0);
+    let overlays = TextOverlays {
+        player: Some((1, 1)),
+        solution: maze.shortest_path((0, 0), (2, 3)),
+        visited: Some(vec![vec![true; 4]; 3]),
+    };
+
+    let expected = concat!(
+        " ___________\n",
+        "|* |*  *  ._|\n",
+        "|*  @ |*_ * |\n",
+        "|._|._|.__*_|\n",
+    );
+    assert_eq!(maze.render_text(TextStyle::Underscore, &overlays), expected);
+
+    let expected = concat!(
+        "┌───┬───────────┐\n",
+        "│ * │ *   *   . │\n",
+        "│   ╵   ╷   ╶───┤\n",
+        "│ *   @ │ *   * │\n",
+        "│   ╷   ├───╴   │\n",
+        "│ . │ . │ .   * │\n",
+        "└───┴───┴───────┘\n",
+    );
+    assert_eq!(maze.render_text(TextStyle::BoxDrawing, &overlays), expected);
+
+    // the markers don't change the drawing underneath
+    let mut written = Vec::new();
+    maze.write_text(&mut written, TextStyle::Blocks, &TextOverlays::default())
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(written).unwrap(),
+        render(&maze, TextStyle::Blocks)
+    );
+}
+
+#[test]
+fn overlays_outside_the_maze_are_left_out() {
+    let maze = Maze::from_seed(3, 4, 0);
+    let overlays = TextOverlays {
+        player: Some((3, 0)),
+        solution: Some(vec![(0, 4), (7, 7), (2, 3)]),
+        visited: Some(vec![vec![true; 9]; 9]),
+    };
+    let inside = TextOverlays {
+        player: None,
+        solution: Some(vec![(2, 3)]),
+        visited: Some(vec![vec![true; 4]; 3]),
+    };
+
+    for style in [
+        TextStyle::Underscore,
+        TextStyle::BoxDrawing,
+        TextStyle::Blocks,
+    ] {
+        assert_eq!(
+            maze.render_text(style, &overlays),
+            maze.render_text(style, &inside)
+        );
+        let mut written = Vec::new();
+        maze.write_text(&mut written, style, &overlays).unwrap();
+    }
+}